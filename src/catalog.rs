@@ -0,0 +1,452 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::Deref,
+    sync::Arc,
+};
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    parenthesized,
+    parse::{ParseStream, Parser},
+    punctuated::Punctuated,
+    Error, Expr, ExprLit, Ident, Lit, LitStr, Result, Token,
+};
+
+use crate::{
+    format_t::placeholder,
+    i18n::{check_yaml, update_yaml, I18nArgs},
+    lang_yaml::{LangYaml, LocalizedText},
+};
+
+/// 名前を指定しなかった時に i18n! が生成するモジュール
+pub const DEFAULT_MODULE: &str = "_langrustang_autogen";
+
+/// 言語ごとに選ばれた値、文字列か、複数形や select の形ごとの文字列
+pub enum Selected<'a> {
+    Text(&'a str),
+    Forms(&'a BTreeMap<String, String>),
+}
+
+impl<'a> Selected<'a> {
+    /// その言語の値を取得する、形ごとの文字列を優先する
+    fn get(localized_text: &'a LocalizedText, lang: &str) -> Option<Self> {
+        match (localized_text.forms.get(lang), localized_text.get(lang)) {
+            (Some(forms), _) => Some(Selected::Forms(forms)),
+            (None, Some(text)) => Some(Selected::Text(text)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// i18n! で読み込んだ yaml
+#[derive(Debug)]
+pub struct Catalog {
+    pub yaml: LangYaml,
+}
+
+impl Catalog {
+    pub fn from_yaml(yaml: LangYaml) -> std::result::Result<Self, String> {
+        // rust の enum に使える文字列かチェック
+        check_yaml::check_yaml(&yaml)?;
+
+        Ok(Self { yaml })
+    }
+
+    /// i18n! の引数のファイルを読み込む (更新されていなければキャッシュを使う)
+    pub fn load(args: &I18nArgs) -> Result<Arc<Self>> {
+        let files = args.files()?;
+
+        update_yaml::load_cached(&files).map_err(|err| Error::new(args.span(), err))
+    }
+}
+
+/// マクロから使う、読み込んだ yaml と i18n! が生成したモジュールの名前
+pub struct CatalogRef {
+    pub catalog: Arc<Catalog>,
+    pub module: Ident,
+    /// all を除いた言語キー
+    pub langs: HashSet<String>,
+    /// 言語ごとの、all の前に試すフォールバック先 (自分自身は含まない)
+    pub fallback: HashMap<String, Vec<String>>,
+    /// デフォルトの言語、フォールバック先の後、all の前に試す
+    pub default: Option<String>,
+}
+
+impl Deref for CatalogRef {
+    type Target = Catalog;
+
+    fn deref(&self) -> &Self::Target {
+        &self.catalog
+    }
+}
+
+impl CatalogRef {
+    /// i18n! の引数の yaml を読み込み、フォールバック先を決める
+    pub fn load(args: &I18nArgs) -> Result<Self> {
+        let catalog = Catalog::load(args)?;
+
+        // i18n! の引数で指定されたものを yaml より優先する
+        let mut declared = catalog.yaml.fallback.clone();
+        for (lang, targets) in args.fallback.iter() {
+            for key in std::iter::once(lang).chain(targets) {
+                check_yaml::check_lang(&key.value()).map_err(|err| Error::new(key.span(), err))?;
+            }
+            declared.insert(lang.value(), targets.iter().map(|t| t.value()).collect());
+        }
+
+        // all を除いてどの言語キーが使われているか取得
+        // フォールバックだけを指定した言語も含める
+        let mut langs = HashSet::new();
+        for (_, localized) in catalog.yaml.iter() {
+            for lang in localized.langs() {
+                if !lang.eq_ignore_ascii_case("all") {
+                    langs.insert(lang.clone());
+                }
+            }
+        }
+        langs.extend(declared.keys().filter(|lang| *lang != "all").cloned());
+
+        let fallback =
+            fallback_chains(&langs, &declared).map_err(|err| Error::new(args.span(), err))?;
+
+        // 情報が書かれた言語が存在するか確認
+        if let Some(lang) = catalog.yaml.meta.keys().find(|lang| !langs.contains(*lang)) {
+            return Err(Error::new(
+                args.span(),
+                format!("Metadata of `{}` has unknown language key", lang),
+            ));
+        }
+
+        // i18n! の引数で指定されたものを yaml より優先する
+        let default = match &args.default {
+            Some(default) => {
+                check_yaml::check_lang(&default.value())
+                    .map_err(|err| Error::new(default.span(), err))?;
+                Some(default.value())
+            }
+            None => catalog.yaml.default.clone(),
+        };
+
+        let catalog_ref = Self {
+            catalog,
+            module: args.module(),
+            langs,
+            fallback,
+            default,
+        };
+        catalog_ref
+            .check_default()
+            .map_err(|err| Error::new(args.span(), err))?;
+
+        Ok(catalog_ref)
+    }
+
+    /// デフォルトの言語が存在し、全てのキーにその言語の値があるか確認する
+    fn check_default(&self) -> std::result::Result<(), String> {
+        let Some(default) = &self.default else {
+            return Ok(());
+        };
+
+        if !self.langs.contains(default) {
+            return Err(format!("Unknown default language: `{}`", default));
+        }
+
+        let mut keys: Vec<_> = self.yaml.iter().collect();
+        keys.sort_by_key(|(key, _)| *key);
+
+        for (key, localized_text) in keys {
+            let has_value = self.chain(default).any(|lang| {
+                localized_text.contains_key(lang) || localized_text.forms.contains_key(lang)
+            });
+
+            if !has_value {
+                return Err(format!(
+                    "Key: {} has no value for the default language `{}`",
+                    key, default
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// ソートした言語キー
+    pub fn sorted_langs(&self) -> Vec<&String> {
+        let mut langs: Vec<_> = self.langs.iter().collect();
+        langs.sort();
+        langs
+    }
+
+    /// 値を探す順番の言語キー、指定された言語、フォールバック先、デフォルトの言語、all の順
+    pub fn chain<'a>(&'a self, lang: &'a str) -> impl Iterator<Item = &'a str> {
+        let fallback = self.fallback.get(lang).into_iter().flatten();
+
+        std::iter::once(lang)
+            .chain(fallback.map(|lang| lang.as_str()))
+            .chain(self.default.as_deref())
+            .chain(std::iter::once("all"))
+    }
+
+    /// 最初の引数の文字列リテラルのキーと、その翻訳を取得する
+    pub fn localized_text(
+        &self,
+        parsed: &Punctuated<Expr, Token![,]>,
+    ) -> std::result::Result<(String, &LocalizedText), String> {
+        let key = match parsed.first() {
+            Some(Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            })) => lit_str.value(),
+
+            _ => return Err("Failed get param".into()),
+        };
+
+        match self.yaml.get(&key) {
+            Some(localized_text) => Ok((key, localized_text)),
+            None => Err(format!("Unknown Key: {}", key)),
+        }
+    }
+
+    /// 全ての言語の値を選ぶ
+    ///
+    /// 言語ごとに、`chain` の順 (その言語、フォールバック先、デフォルトの言語、all) で最初に見つかった値を使い、
+    /// 見つからない言語があればエラーを返す
+    pub fn select<'a, T>(
+        &'a self,
+        find: impl Fn(&'a str) -> Option<T>,
+    ) -> std::result::Result<Vec<(&'a String, T)>, String> {
+        let mut selected = vec![];
+        let mut missing = vec![];
+
+        for lang in self.sorted_langs() {
+            match self.chain(lang).find_map(&find) {
+                Some(value) => selected.push((lang, value)),
+                None => missing.push(lang),
+            }
+        }
+
+        match missing.is_empty() {
+            true => Ok(selected),
+            false => Err(format!("Missing language key: {:?}", missing)),
+        }
+    }
+
+    /// 複数形や select のキーの、全ての言語の値を選ぶ
+    ///
+    /// 形ごとの文字列も含めて、プレースホルダが全て一致しているか確認する
+    pub fn select_forms<'a>(
+        &'a self,
+        key: &str,
+        localized_text: &'a LocalizedText,
+        args: &[&Expr],
+        implicit: &[&str],
+    ) -> std::result::Result<Vec<(&'a String, Selected<'a>)>, String> {
+        let selected = self.select(|lang| Selected::get(localized_text, lang))?;
+
+        let mut templates = vec![];
+        for (lang, value) in selected.iter() {
+            match value {
+                Selected::Text(text) => templates.push((lang.to_string(), *text)),
+                Selected::Forms(forms) => {
+                    for (case, text) in forms.iter() {
+                        templates.push((format!("{}.{}", lang, case), text.as_str()));
+                    }
+                }
+            }
+        }
+
+        placeholder::check_templates(key, &templates, args, implicit)?;
+
+        Ok(selected)
+    }
+
+    /// マクロの引数を読み込む、`@current` は現在の言語 (`current_lang()`) にする
+    pub fn parse_args(&self, input: ParseStream) -> Result<Punctuated<Expr, Token![,]>> {
+        let module_path = self.module_path();
+        let tokens: TokenStream = input.parse()?;
+
+        let mut replaced = TokenStream::new();
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match (&token, tokens.peek()) {
+                (TokenTree::Punct(punct), Some(TokenTree::Ident(ident)))
+                    if punct.as_char() == '@' && ident == "current" =>
+                {
+                    tokens.next();
+                    replaced.extend(quote! { #module_path::current_lang() });
+                }
+
+                _ => replaced.extend([token]),
+            }
+        }
+
+        Punctuated::parse_terminated.parse2(replaced)
+    }
+
+    /// i18n! が生成したモジュールのパス
+    pub fn module_path(&self) -> TokenStream {
+        let module = &self.module;
+        quote! { crate::#module }
+    }
+}
+
+/// i18n! が生成したマクロから渡される `@catalog (...)` を読み込む、無ければ None
+///
+/// None の時は、マクロを `redirect` で i18n! が生成したマクロを経由して呼び出し直す
+pub fn parse(input: ParseStream) -> Result<Option<CatalogRef>> {
+    if !input.peek(Token![@]) {
+        return Ok(None);
+    }
+
+    input.parse::<Token![@]>()?;
+    let ident: Ident = input.parse()?;
+    if ident != "catalog" {
+        return Err(Error::new(ident.span(), "expected `@catalog`"));
+    }
+
+    let content;
+    parenthesized!(content in input);
+    let args: I18nArgs = content.parse()?;
+
+    CatalogRef::load(&args).map(Some)
+}
+
+/// 指定されたフォールバック先をたどって、言語ごとのフォールバックの順番を決める
+///
+/// `pt_br: pt`, `pt: en` なら pt_br は pt, en の順になる
+fn fallback_chains(
+    langs: &HashSet<String>,
+    declared: &BTreeMap<String, Vec<String>>,
+) -> std::result::Result<HashMap<String, Vec<String>>, String> {
+    fn visit(
+        lang: &str,
+        declared: &BTreeMap<String, Vec<String>>,
+        stack: &mut Vec<String>,
+        chain: &mut Vec<String>,
+    ) -> std::result::Result<(), String> {
+        for target in declared.get(lang).into_iter().flatten() {
+            if stack.contains(target) {
+                return Err(format!("Fallback of `{}` is circular", stack[0]));
+            }
+
+            if !chain.contains(target) {
+                chain.push(target.clone());
+            }
+
+            stack.push(target.clone());
+            visit(target, declared, stack, chain)?;
+            stack.pop();
+        }
+
+        Ok(())
+    }
+
+    let mut chains = HashMap::new();
+    for (lang, targets) in declared.iter() {
+        for key in std::iter::once(lang).chain(targets) {
+            if !langs.contains(key) {
+                return Err(format!(
+                    "Fallback of `{}` has unknown language key `{}`",
+                    lang, key
+                ));
+            }
+        }
+
+        let mut chain = vec![];
+        visit(lang, declared, &mut vec![lang.clone()], &mut chain)?;
+        chains.insert(lang.clone(), chain);
+    }
+
+    Ok(chains)
+}
+
+/// i18n! が生成したマクロを経由して、`@catalog (...)` 付きでもう一度マクロを呼び出す
+///
+/// 最初の引数が `ui,` のように名前なら、`i18n!(name = ui, ...)` で生成したモジュールを使う
+pub fn redirect(macro_name: &str, input: ParseStream) -> Result<TokenStream> {
+    let has_name = input.peek(Ident) && input.peek2(Token![,]);
+    redirect_to(macro_name, input, has_name)
+}
+
+/// write_t! のように最初の引数が書き込み先のマクロを、`@catalog (...)` 付きでもう一度呼び出す
+///
+/// `ui, f, "key"` なら ui が名前、`f, "key"` なら f は書き込み先になる
+pub fn redirect_with_dst(macro_name: &str, input: ParseStream) -> Result<TokenStream> {
+    // 名前、書き込み先 (文字列リテラル以外)、キーの文字列リテラルの順になっているか
+    let fork = input.fork();
+    let has_name = fork.parse::<Ident>().is_ok()
+        && fork.parse::<Token![,]>().is_ok()
+        && fork.parse::<Expr>().is_ok_and(|dst| {
+            !matches!(
+                dst,
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(_),
+                    ..
+                })
+            )
+        })
+        && fork.parse::<Token![,]>().is_ok()
+        && fork.peek(LitStr);
+
+    redirect_to(macro_name, input, has_name)
+}
+
+fn redirect_to(macro_name: &str, input: ParseStream, has_name: bool) -> Result<TokenStream> {
+    let module = match has_name {
+        true => {
+            let name: Ident = input.parse()?;
+            input.parse::<Token![,]>()?;
+            name
+        }
+        false => Ident::new(DEFAULT_MODULE, Span::call_site()),
+    };
+
+    let tokens: TokenStream = input.parse()?;
+    let macro_name = Ident::new(macro_name, Span::call_site());
+
+    Ok(quote! {
+        crate::#module::__langrustang_catalog! { #macro_name ! { #tokens } }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn declared(pairs: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(lang, targets)| {
+                (
+                    lang.to_string(),
+                    targets.iter().map(|t| t.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chains() {
+        let langs: HashSet<_> = ["pt_br", "pt", "es", "en"].map(String::from).into();
+
+        let chains = fallback_chains(
+            &langs,
+            &declared(&[("pt_br", &["pt"]), ("pt", &["es", "en"]), ("es", &["en"])]),
+        )
+        .unwrap();
+        assert_eq!(chains["pt_br"], ["pt", "es", "en"]);
+        assert_eq!(chains["pt"], ["es", "en"]);
+
+        let err = fallback_chains(&langs, &declared(&[("pt", &["es"]), ("es", &["pt"])]));
+        assert_eq!(err.unwrap_err(), "Fallback of `es` is circular");
+
+        let err = fallback_chains(&langs, &declared(&[("pt", &["fr"])]));
+        assert_eq!(
+            err.unwrap_err(),
+            "Fallback of `pt` has unknown language key `fr`"
+        );
+    }
+}
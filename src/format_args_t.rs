@@ -0,0 +1,170 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{ParseStream, Parser},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Error, Expr, ExprAssign, Ident, Result, Token,
+};
+
+use crate::{
+    catalog,
+    format_t::{
+        create_literal,
+        format_macro::{FormatCall, FormatMacro},
+    },
+    select_t,
+};
+
+pub fn _format_args_t(tokens: TokenStream) -> TokenStream {
+    format_args_t_parse
+        .parse2(tokens)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+fn format_args_t_parse(input: ParseStream) -> Result<TokenStream> {
+    let Some(catalog) = catalog::parse(input)? else {
+        return catalog::redirect("format_args_t", input);
+    };
+
+    let parsed = catalog.parse_args(input)?;
+
+    // 簡単にリターンできる用のクロージャ
+    let err_return = |s: String| Err(Error::new(parsed.span(), s));
+
+    if parsed.is_empty() {
+        return Err(Error::new(input.span(), "Expected string literal"));
+    }
+
+    // 指定されたキーの翻訳を取得
+    let (key, localized_text) = match catalog.localized_text(&parsed) {
+        Ok(found) => found,
+        Err(err) => return err_return(err),
+    };
+
+    // 複数形や select の場合は plural_t! か select_t! を使う
+    if !localized_text.forms.is_empty() {
+        return err_return(select_t::forms_error(&key, localized_text));
+    }
+
+    // 言語キーが all のみかどうか
+    let is_allonly_key =
+        localized_text.len() == 1 && localized_text.iter().any(|(lang, _)| lang == "all");
+
+    // 引数は FormatArgs に一度だけ評価して渡し、クロージャの中では束縛した名前で使う
+    let mut captured = vec![];
+    let mut bindings = vec![];
+    let mut replaced: Punctuated<Expr, Token![,]> = Punctuated::new();
+
+    for (i, expr) in parsed.iter().enumerate() {
+        let binding = Ident::new(&format!("__langrustang_arg{}", i), Span::call_site());
+
+        match (i, expr) {
+            (0, key) => replaced.push(key.clone()),
+
+            // 言語はそのまま渡す
+            (1, lang) if !is_allonly_key => {
+                captured.push(quote! { #lang });
+                replaced.push(parse_quote! { *#binding });
+                bindings.push(binding);
+            }
+
+            // user = expr は user = 束縛した名前 にする
+            (_, Expr::Assign(ExprAssign { left, right, .. })) => {
+                captured.push(quote! { &#right });
+                replaced.push(parse_quote! { #left = #binding });
+                bindings.push(binding);
+            }
+
+            (_, expr) => {
+                captured.push(quote! { &#expr });
+                replaced.push(parse_quote! { #binding });
+                bindings.push(binding);
+            }
+        }
+    }
+
+    let call = FormatCall {
+        format_macro: FormatMacro::Write,
+        dst: Some(parse_quote! { __langrustang_f }),
+        module_path: catalog.module_path(),
+    };
+
+    let body = match is_allonly_key {
+        true => create_literal::allkey_only(replaced, localized_text, &key, &call)?,
+        false => create_literal::not_allkey_only(replaced, localized_text, &key, &catalog, &call)?,
+    };
+
+    let module_path = catalog.module_path();
+
+    Ok(quote! {
+        #module_path::FormatArgs::new(
+            (#(#captured,)*),
+            |(#(#bindings,)*), __langrustang_f| #body,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn dbg() {
+        let token = _format_args_t(
+            quote! { @catalog ("files/test_file.yaml") "format3", lang, 1, user = name },
+        )
+        .to_string();
+        dbg!(token);
+    }
+
+    #[test]
+    fn allonly() {
+        let token1 =
+            _format_args_t(quote! { @catalog ("files/test_file.yaml") "format1", arg1, arg2 })
+                .to_string();
+        let token2 = quote! {
+            crate::_langrustang_autogen::FormatArgs::new(
+                (&arg1, &arg2,),
+                |(__langrustang_arg1, __langrustang_arg2,), __langrustang_f| ::core::write!(__langrustang_f, "ALL {} {}", __langrustang_arg1, __langrustang_arg2),
+            )
+        }
+        .to_string();
+
+        assert_eq!(token1, token2)
+    }
+
+    #[test]
+    fn lang_and_named() {
+        let token1 = _format_args_t(
+            quote! { @catalog ("files/test_file.yaml") "format3", lang, 1, user = name },
+        )
+        .to_string();
+        let token2 = quote! {
+            crate::_langrustang_autogen::FormatArgs::new(
+                (lang, &1, &name,),
+                |(__langrustang_arg1, __langrustang_arg2, __langrustang_arg3,), __langrustang_f| match *__langrustang_arg1 {
+                    crate::_langrustang_autogen::Lang::En => ::core::write!(__langrustang_f, "{0}, {user}!", __langrustang_arg2, user = __langrustang_arg3),
+                    crate::_langrustang_autogen::Lang::Ja => ::core::write!(__langrustang_f, "{user}さん、{0}", __langrustang_arg2, user = __langrustang_arg3),
+                    crate::_langrustang_autogen::Lang::Test1 => ::core::write!(__langrustang_f, "{0:>8} {user}", __langrustang_arg2, user = __langrustang_arg3),
+                    crate::_langrustang_autogen::Lang::Zh => ::core::write!(__langrustang_f, "{user} {0}", __langrustang_arg2, user = __langrustang_arg3),
+                },
+            )
+        }
+        .to_string();
+
+        assert_eq!(token1, token2)
+    }
+
+    #[test]
+    fn missing_named_arg() {
+        let token = _format_args_t(quote! { @catalog ("files/test_file.yaml") "format3", lang, 1 })
+            .to_string();
+        assert!(dbg!(token).contains("Key: format3 requires named argument `user`"));
+    }
+}
@@ -1,73 +1,99 @@
-mod create_literal;
+pub mod create_literal;
+pub mod format_macro;
+pub mod placeholder;
 
 use proc_macro2::TokenStream;
 use syn::{
     parse::{ParseStream, Parser},
-    punctuated::Punctuated,
     spanned::Spanned,
-    Error, Expr, ExprLit, Lit, Result, Token,
+    Error, Result,
 };
 
-use crate::{i18n::update_yaml::if_update_reload_yaml, YAML_DATA};
+use crate::{
+    catalog::{self, CatalogRef},
+    select_t,
+};
+
+use format_macro::{FormatCall, FormatMacro};
 
 pub fn _format_t(tokens: TokenStream) -> TokenStream {
-    format_t_parse
+    _format_macro_t(FormatMacro::Format, tokens)
+}
+
+/// format_t! と同じ引数で、format! 以外の std のマクロ (println! や write! など) を呼び出す
+pub fn _format_macro_t(format_macro: FormatMacro, tokens: TokenStream) -> TokenStream {
+    (|input: ParseStream| format_macro_t_parse(format_macro, input))
         .parse2(tokens)
         .unwrap_or_else(Error::into_compile_error)
 }
 
-pub fn format_t_parse(input: ParseStream) -> Result<TokenStream> {
-    let parsed = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+fn format_macro_t_parse(format_macro: FormatMacro, input: ParseStream) -> Result<TokenStream> {
+    let Some(catalog) = catalog::parse(input)? else {
+        // 使えない feature なら、i18n! を経由する前に返す
+        if let Err(err) = format_macro.check_feature() {
+            return Err(Error::new(input.span(), err));
+        }
 
-    // 簡単にリターンできる用のクロージャ
-    let err_return = |s: String| Err(Error::new(parsed.span(), s));
+        return match format_macro.has_dst() {
+            true => catalog::redirect_with_dst(format_macro.name(), input),
+            false => catalog::redirect(format_macro.name(), input),
+        };
+    };
 
-    // yamldata の取得 i18n が使われていなかったら返す
-    let yaml_data = {
-        let lock = YAML_DATA.lock().unwrap();
+    format_t_catalog(input, &catalog, format_macro)
+}
 
-        match lock.as_ref() {
-            Some(value) => value.clone(),
-            None => {
-                return err_return(
-                    "langrustang::i18n is not used, please set the yaml path.".into(),
-                )
-            }
+/// 読み込んだ yaml から format! などの呼び出しを作成する
+fn format_t_catalog(
+    input: ParseStream,
+    catalog: &CatalogRef,
+    format_macro: FormatMacro,
+) -> Result<TokenStream> {
+    let mut parsed = catalog.parse_args(input)?;
+
+    // write! の書き込み先を取り出す
+    let dst = match format_macro.has_dst() {
+        true => {
+            let mut args = parsed.into_iter();
+            let Some(dst) = args.next() else {
+                return Err(Error::new(input.span(), "Expected destination"));
+            };
+            parsed = args.collect();
+            Some(dst)
         }
+        false => None,
     };
-
-    // yaml が更新されていたら static を更新
-    let _ = if_update_reload_yaml();
-
-    match parsed.len() {
-        0 => return Err(Error::new(input.span(), "Expected string literal")),
-        _ => (),
+    let call = FormatCall {
+        format_macro,
+        dst,
+        module_path: catalog.module_path(),
     };
 
-    // 指定された文字列リテラルを取得
-    let key = {
-        match parsed.get(0) {
-            Some(Expr::Lit(ExprLit {
-                lit: Lit::Str(lit_str),
-                ..
-            })) => lit_str.value(),
+    // 簡単にリターンできる用のクロージャ
+    let err_return = |s: String| Err(Error::new(parsed.span(), s));
 
-            _ => return err_return("Failed get param".into()),
-        }
-    };
+    if parsed.is_empty() {
+        return Err(Error::new(input.span(), "Expected string literal"));
+    }
 
-    // 存在しないキーなら返す
-    let Some(localized_text) = yaml_data.get(&key) else {
-        return err_return(format!("Unknown Key: {}", key));
+    // 指定されたキーの翻訳を取得
+    let (key, localized_text) = match catalog.localized_text(&parsed) {
+        Ok(found) => found,
+        Err(err) => return err_return(err),
     };
 
+    // 複数形や select の場合は plural_t! か select_t! を使う
+    if !localized_text.forms.is_empty() {
+        return err_return(select_t::forms_error(&key, localized_text));
+    }
+
     // 言語キーが all のみかどうか
     let is_allonly_key =
         localized_text.len() == 1 && localized_text.iter().any(|(lang, _)| lang == "all");
 
     match is_allonly_key {
-        true => create_literal::allkey_only(parsed, localized_text),
-        false => create_literal::not_allkey_only(parsed, localized_text),
+        true => create_literal::allkey_only(parsed, localized_text, &key, &call),
+        false => create_literal::not_allkey_only(parsed, localized_text, &key, catalog, &call),
     }
 }
 
@@ -76,62 +102,60 @@ mod tests {
     use pretty_assertions::assert_eq;
     use quote::quote;
 
-    use crate::i18n::_i18n;
-
     use super::*;
 
     #[test]
     #[ignore]
     fn dbg() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token = _format_t(quote! { "example1" }).to_string();
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") "example1" }).to_string();
         dbg!(token);
     }
 
     #[test]
     fn allonly_arg_1() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _format_t(quote! { "example1" }).to_string();
-        let token2 = quote! { format!("ALL_EXAMPLE") }.to_string();
+        let token1 = _format_t(quote! { @catalog ("files/test_file.yaml") "example1" }).to_string();
+        let token2 =
+            quote! { crate::_langrustang_autogen::__langrustang_alloc::format!("ALL_EXAMPLE") }
+                .to_string();
 
         assert_eq!(token1, token2)
     }
 
     #[test]
     fn allonly_arg_2to() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _format_t(quote! { "example1", arg1, arg2 }).to_string();
-        let token2 = quote! { format!("ALL_EXAMPLE", arg1, arg2) }.to_string();
+        let token1 = _format_t(quote! { @catalog ("files/test_file.yaml") "format1", arg1, arg2 })
+            .to_string();
+        let token2 = quote! { crate::_langrustang_autogen::__langrustang_alloc::format!("ALL {} {}", arg1, arg2) }.to_string();
 
         assert_eq!(token1, token2)
     }
 
     #[test]
-    fn lang_arg_1_not_all() {
-        _i18n(quote! {"files/test_file.yaml"});
+    fn allonly_too_many_args() {
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") "example1", arg1, arg2 })
+            .to_string();
+        assert!(
+            dbg!(token).contains("Key: example1 expects 0 positional arguments, but 2 were given")
+        )
+    }
 
-        let token = _format_t(quote! { "example5", lang }).to_string();
+    #[test]
+    fn lang_arg_1_not_all() {
+        let token =
+            _format_t(quote! { @catalog ("files/test_file.yaml") "example5", lang }).to_string();
         assert!(dbg!(token).contains("Missing language key"))
     }
 
     #[test]
     fn lang_arg_1_all() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _format_t(quote! { "example4", lang }).to_string();
+        let token1 =
+            _format_t(quote! { @catalog ("files/test_file.yaml") "example4", lang }).to_string();
         let token2 = quote! {
-            {
-                use crate::_langrustang_autogen::Lang::*;
-
-                match lang {
-                    En => format!("ALL"),
-                    Ja => format!("おはよう"),
-                    Test1 => format!("ALL"),
-                    Zh => format!("ALL"),
-                }
+            match lang {
+                crate::_langrustang_autogen::Lang::En => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL"),
+                crate::_langrustang_autogen::Lang::Ja => crate::_langrustang_autogen::__langrustang_alloc::format!("おはよう"),
+                crate::_langrustang_autogen::Lang::Test1 => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL"),
+                crate::_langrustang_autogen::Lang::Zh => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL"),
             }
         }
         .to_string();
@@ -140,19 +164,15 @@ mod tests {
     }
     #[test]
     fn lang_arg_2to_all() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _format_t(quote! { "example4", lang, arg1, arg2 }).to_string();
+        let token1 =
+            _format_t(quote! { @catalog ("files/test_file.yaml") "format2", lang, arg1, arg2 })
+                .to_string();
         let token2 = quote! {
-            {
-                use crate::_langrustang_autogen::Lang::*;
-
-                match lang {
-                    En => format!("ALL", arg1, arg2),
-                    Ja => format!("おはよう", arg1, arg2),
-                    Test1 => format!("ALL", arg1, arg2),
-                    Zh => format!("ALL", arg1, arg2),
-                }
+            match lang {
+                crate::_langrustang_autogen::Lang::En => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Ja => crate::_langrustang_autogen::__langrustang_alloc::format!("おはよう {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Test1 => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Zh => crate::_langrustang_autogen::__langrustang_alloc::format!("ALL {} {}", arg1, arg2),
             }
         }
         .to_string();
@@ -162,19 +182,16 @@ mod tests {
 
     #[test]
     fn lang_arg_2to_not_all() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _format_t(quote! { "example2", lang, arg1, arg2 }).to_string();
+        let token1 = _format_t(
+            quote! { @catalog ("files/test_file.yaml") "format3", lang, arg1, user = name },
+        )
+        .to_string();
         let token2 = quote! {
-            {
-                use crate::_langrustang_autogen::Lang::*;
-
-                match lang {
-                    En => format!("hello!", arg1, arg2),
-                    Ja => format!("おはよう", arg1, arg2),
-                    Test1 => format!("TEST1", arg1, arg2),
-                    Zh => format!("你好", arg1, arg2),
-                }
+            match lang {
+                crate::_langrustang_autogen::Lang::En => crate::_langrustang_autogen::__langrustang_alloc::format!("{0}, {user}!", arg1, user = name),
+                crate::_langrustang_autogen::Lang::Ja => crate::_langrustang_autogen::__langrustang_alloc::format!("{user}さん、{0}", arg1, user = name),
+                crate::_langrustang_autogen::Lang::Test1 => crate::_langrustang_autogen::__langrustang_alloc::format!("{0:>8} {user}", arg1, user = name),
+                crate::_langrustang_autogen::Lang::Zh => crate::_langrustang_autogen::__langrustang_alloc::format!("{user} {0}", arg1, user = name),
             }
         }
         .to_string();
@@ -183,18 +200,132 @@ mod tests {
     }
 
     #[test]
-    fn expect_str() {
-        _i18n(quote! {"files/test_file.yaml"});
+    fn placeholder_mismatch() {
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") "format4", lang, arg1 })
+            .to_string();
+        assert!(dbg!(token).contains("Key: format4, language `ja` has {0}, {1} but `en` has {0}"))
+    }
 
-        let token = _format_t(quote! {}).to_string();
+    #[test]
+    fn missing_named_arg() {
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") "format3", lang, arg1 })
+            .to_string();
+        assert!(dbg!(token).contains("Key: format3 requires named argument `user`"))
+    }
+
+    #[test]
+    fn expect_str() {
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") }).to_string();
         assert!(dbg!(token).contains("Expected string literal"))
     }
 
     #[test]
     fn expect_lang() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token = _format_t(quote! { "example2" }).to_string();
+        let token = _format_t(quote! { @catalog ("files/test_file.yaml") "example2" }).to_string();
         assert!(dbg!(token).contains("Expected lang"))
     }
+
+    #[test]
+    fn write_dst() {
+        let token1 = _format_macro_t(
+            FormatMacro::Writeln,
+            quote! { @catalog ("files/test_file.yaml") f, "format2", lang, arg1, arg2 },
+        )
+        .to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => ::core::writeln!(f, "ALL {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Ja => ::core::writeln!(f, "おはよう {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Test1 => ::core::writeln!(f, "ALL {} {}", arg1, arg2),
+                crate::_langrustang_autogen::Lang::Zh => ::core::writeln!(f, "ALL {} {}", arg1, arg2),
+            }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+
+        let token1 = _format_macro_t(
+            FormatMacro::Panic,
+            quote! { @catalog ("files/test_file.yaml") "format1", arg1, arg2 },
+        )
+        .to_string();
+        let token2 = quote! { ::core::panic!("ALL {} {}", arg1, arg2) }.to_string();
+        assert_eq!(token1, token2);
+    }
+
+    #[test]
+    fn write_redirect() {
+        // 書き込み先は名前として扱わない
+        let token1 =
+            _format_macro_t(FormatMacro::Write, quote! { f, "example2", lang }).to_string();
+        let token2 = quote! {
+            crate::_langrustang_autogen::__langrustang_catalog! { write_t! { f, "example2", lang } }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+
+        let token1 =
+            _format_macro_t(FormatMacro::Write, quote! { f, "format1", "a", "b" }).to_string();
+        assert!(token1.contains("_langrustang_autogen"));
+
+        let token1 = _format_macro_t(
+            FormatMacro::Write,
+            quote! { ui, &mut out, "example2", lang },
+        )
+        .to_string();
+        let token2 = quote! {
+            crate::ui::__langrustang_catalog! { write_t! { &mut out, "example2", lang } }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+    }
+
+    #[test]
+    fn expect_dst() {
+        let token = _format_macro_t(
+            FormatMacro::Write,
+            quote! { @catalog ("files/test_file.yaml") },
+        )
+        .to_string();
+        assert!(dbg!(token).contains("Expected destination"))
+    }
+
+    #[test]
+    fn println_nested_macro() {
+        // 引数の中の format! や format という名前は書き換えない
+        let token1 = _format_macro_t(
+            FormatMacro::Println,
+            quote! { @catalog ("files/test_file.yaml") "format2", lang, format!("{x}"), self.format },
+        )
+        .to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => ::std::println!("ALL {} {}", format!("{x}"), self.format),
+                crate::_langrustang_autogen::Lang::Ja => ::std::println!("おはよう {} {}", format!("{x}"), self.format),
+                crate::_langrustang_autogen::Lang::Test1 => ::std::println!("ALL {} {}", format!("{x}"), self.format),
+                crate::_langrustang_autogen::Lang::Zh => ::std::println!("ALL {} {}", format!("{x}"), self.format),
+            }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+    }
+
+    #[test]
+    fn print_format_binding() {
+        let token1 = _format_macro_t(
+            FormatMacro::Print,
+            quote! { @catalog ("files/test_file.yaml") "format1", format, { let format = 1; format } },
+        )
+        .to_string();
+        let token2 =
+            quote! { ::std::print!("ALL {} {}", format, { let format = 1; format }) }.to_string();
+        assert_eq!(token1, token2);
+
+        let token1 = _format_macro_t(
+            FormatMacro::Println,
+            quote! { @catalog ("files/test_file.yaml") "format3", lang, 1, user = format },
+        )
+        .to_string();
+        assert!(token1
+            .contains(&quote! { ::std::println!("{0}, {user}!", 1, user = format) }.to_string()));
+    }
 }
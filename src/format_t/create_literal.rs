@@ -1,15 +1,17 @@
-use std::collections::HashSet;
-
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{punctuated::Punctuated, spanned::Spanned, token::Comma, Error, Expr, Ident, Result};
 
-use crate::{i18n::check_yaml, lang_yaml::LocalizedText, YAML_LANGS};
+use crate::{catalog::CatalogRef, i18n::check_yaml, lang_yaml::LocalizedText};
+
+use super::{format_macro::FormatCall, placeholder};
 
 /// allキーのみの時の処理
 pub fn allkey_only(
     parsed: Punctuated<Expr, Comma>,
     localized_text: &LocalizedText,
+    key: &str,
+    call: &FormatCall,
 ) -> Result<TokenStream> {
     let err_return = |s: String| Err(Error::new(parsed.span(), s));
 
@@ -18,28 +20,22 @@ pub fn allkey_only(
         None => return err_return("Failed to get all key".into()),
     };
 
-    // リテラルのみならその値を format! に渡す
-    match parsed.len() {
-        1 => return Ok(quote! { format!( #value ) }),
-        2.. => (),
-        _ => unreachable!(),
-    };
-
-    // 最初のリテラル以外の引数を取得
-    let mut args = Vec::new();
-    for (i, expr) in parsed.into_iter().enumerate() {
-        if i != 0 {
-            args.push(expr);
-        }
+    // プレースホルダと引数が一致しているか確認
+    let args: Vec<_> = parsed.iter().skip(1).collect();
+    if let Err(err) = placeholder::check_args(key, localized_text, &args) {
+        return err_return(err);
     }
 
-    Ok(quote! { format!( #value, #(#args),* ) })
+    Ok(call.tokens(value, &args))
 }
 
 /// allキー以外もあるの時の処理
 pub fn not_allkey_only(
     parsed: Punctuated<Expr, Comma>,
     localized_text: &LocalizedText,
+    key: &str,
+    catalog: &CatalogRef,
+    call: &FormatCall,
 ) -> Result<TokenStream> {
     let err_return = |s: String| Err(Error::new(parsed.span(), s));
 
@@ -48,16 +44,6 @@ pub fn not_allkey_only(
         return err_return("Expected lang".into());
     }
 
-    let yaml_langs = {
-        let lock = YAML_LANGS.lock().unwrap();
-        lock.clone()
-    };
-
-    // all を含むかで分岐
-    let is_containts_all = localized_text
-        .iter()
-        .any(|(key, _)| key.to_ascii_lowercase() == "all");
-
     // format に渡す引数と lang を取得
     let mut args = Vec::new();
     for (i, expr) in parsed.iter().enumerate() {
@@ -67,80 +53,27 @@ pub fn not_allkey_only(
     }
     let lang_expr = args.remove(0);
 
-    // match arm の作成
-    let mut idents = vec![];
-    let mut strings = vec![];
-
-    let mut sorted_yaml_langs: Vec<_> = yaml_langs.iter().collect();
-    sorted_yaml_langs.sort();
-
-    let match_arms: Vec<_> = if is_containts_all {
-        for i in sorted_yaml_langs {
-            let enum_key = Ident::new(&check_yaml::to_enumval_format(i), Span::call_site());
-
-            // 存在しないキーの場合、allキーのリテラルを適応する
-            idents.push(enum_key);
-            strings.push(
-                localized_text
-                    .get(i)
-                    .unwrap_or_else(|| localized_text.get("all").unwrap()),
-            );
-        }
-
-        idents
-            .iter()
-            .zip(strings.iter())
-            .map(|(i, s)| {
-                quote! {
-                    #i => format!(#s #(, #args)* )
-                }
-            })
-            .collect()
-    } else {
-        // 数が足りているかチェックして足りなければ返す
-        if yaml_langs.len() > localized_text.len() {
-            let localized_lang: HashSet<_> =
-                localized_text.iter().map(|(k, _)| k.clone()).collect();
-
-            let mut missing: HashSet<_> = yaml_langs.difference(&localized_lang).collect();
-            missing.remove(&"all".to_string());
-
-            let mut sorted_missing: Vec<_> = missing.iter().collect();
-            sorted_missing.sort();
-
-            return err_return(format!("Missing language key: {:?}", sorted_missing));
-        }
-
-        for i in sorted_yaml_langs {
-            let enum_key = Ident::new(&check_yaml::to_enumval_format(i), Span::call_site());
-
-            // 存在しないキーの場合、allキーのリテラルを適応する
-            idents.push(enum_key);
-            strings.push(
-                localized_text
-                    .get(i)
-                    .unwrap_or_else(|| localized_text.get("all").unwrap()),
-            );
-        }
+    // プレースホルダと引数が一致しているか確認
+    if let Err(err) = placeholder::check_args(key, localized_text, &args) {
+        return err_return(err);
+    }
 
-        idents
-            .iter()
-            .zip(strings.iter())
-            .map(|(i, s)| {
-                quote! {
-                    #i => format!(#s #(, #args)* )
-                }
-            })
-            .collect()
+    // 言語ごとの値を選ぶ、足りない言語があれば返す
+    let selected = match catalog.select(|lang| localized_text.get(lang)) {
+        Ok(selected) => selected,
+        Err(err) => return err_return(err),
     };
 
-    Ok(quote! {
-        {
-            use crate::_langrustang_autogen::Lang::*;
+    let module_path = catalog.module_path();
+    let match_arms = selected.iter().map(|(lang, text)| {
+        let ident = Ident::new(&check_yaml::to_enumval_format(lang), Span::call_site());
+        let call = call.tokens(text, &args);
+        quote! { #module_path::Lang::#ident => #call }
+    });
 
-            match #lang_expr {
-                #(#match_arms),* ,
-            }
+    Ok(quote! {
+        match #lang_expr {
+            #(#match_arms),* ,
         }
     })
 }
@@ -0,0 +1,150 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Expr;
+
+/// 生成するコードが使う langrustang の feature が無効ならエラーを返す
+pub fn check_feature(name: &str, feature: &str, enabled: bool) -> Result<(), String> {
+    match enabled {
+        true => Ok(()),
+        false => Err(format!(
+            "`{}!` requires the `{}` feature of langrustang",
+            name, feature
+        )),
+    }
+}
+
+/// format_t! と同じ引数で文字列を出力する、生成先の std のマクロ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatMacro {
+    Format,
+    Print,
+    Println,
+    Eprint,
+    Eprintln,
+    Write,
+    Writeln,
+    Panic,
+}
+
+impl FormatMacro {
+    /// langrustang のマクロの名前
+    pub fn name(self) -> &'static str {
+        match self {
+            FormatMacro::Format => "format_t",
+            FormatMacro::Print => "print_t",
+            FormatMacro::Println => "println_t",
+            FormatMacro::Eprint => "eprint_t",
+            FormatMacro::Eprintln => "eprintln_t",
+            FormatMacro::Write => "write_t",
+            FormatMacro::Writeln => "writeln_t",
+            FormatMacro::Panic => "panic_t",
+        }
+    }
+
+    /// 生成するコードが使う feature が有効か確認する
+    ///
+    /// format! は alloc、print! などは std が必要で、write! と panic! は core だけで使える
+    pub fn check_feature(self) -> Result<(), String> {
+        match self {
+            FormatMacro::Format => check_feature(self.name(), "alloc", cfg!(feature = "alloc")),
+            FormatMacro::Print
+            | FormatMacro::Println
+            | FormatMacro::Eprint
+            | FormatMacro::Eprintln => check_feature(self.name(), "std", cfg!(feature = "std")),
+            FormatMacro::Write | FormatMacro::Writeln | FormatMacro::Panic => Ok(()),
+        }
+    }
+
+    /// 生成する std のマクロのパス
+    ///
+    /// format! は no_std のクレートでも使えるように、生成されたモジュールが読み込んだ alloc のものを使う
+    fn std_path(self, module_path: &TokenStream) -> TokenStream {
+        match self {
+            FormatMacro::Format => quote! { #module_path::__langrustang_alloc::format },
+            FormatMacro::Print => quote! { ::std::print },
+            FormatMacro::Println => quote! { ::std::println },
+            FormatMacro::Eprint => quote! { ::std::eprint },
+            FormatMacro::Eprintln => quote! { ::std::eprintln },
+            FormatMacro::Write => quote! { ::core::write },
+            FormatMacro::Writeln => quote! { ::core::writeln },
+            FormatMacro::Panic => quote! { ::core::panic },
+        }
+    }
+
+    /// 最初の引数に書き込み先を受け取るか
+    pub fn has_dst(self) -> bool {
+        matches!(self, FormatMacro::Write | FormatMacro::Writeln)
+    }
+}
+
+/// 生成するマクロの呼び出し、write! の時は書き込み先も持つ
+pub struct FormatCall {
+    pub format_macro: FormatMacro,
+    pub dst: Option<Expr>,
+    pub module_path: TokenStream,
+}
+
+impl FormatCall {
+    /// 文字列と引数からマクロの呼び出しを作成する
+    pub fn tokens(&self, text: &str, args: &[&Expr]) -> TokenStream {
+        let path = self.format_macro.std_path(&self.module_path);
+
+        match &self.dst {
+            Some(dst) => quote! { #path!(#dst, #text #(, #args)*) },
+            None => quote! { #path!(#text #(, #args)*) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn call_tokens() {
+        let arg: Expr = parse_quote! { name };
+
+        let call = FormatCall {
+            format_macro: FormatMacro::Eprintln,
+            dst: None,
+            module_path: quote! { crate::_langrustang_autogen },
+        };
+        assert_eq!(
+            call.tokens("Hi, {}!", &[&arg]).to_string(),
+            quote! { ::std::eprintln!("Hi, {}!", name) }.to_string()
+        );
+
+        let call = FormatCall {
+            format_macro: FormatMacro::Write,
+            dst: Some(parse_quote! { f }),
+            module_path: quote! { crate::_langrustang_autogen },
+        };
+        assert_eq!(
+            call.tokens("Hi!", &[]).to_string(),
+            quote! { ::core::write!(f, "Hi!") }.to_string()
+        );
+
+        let call = FormatCall {
+            format_macro: FormatMacro::Format,
+            dst: None,
+            module_path: quote! { crate::ui },
+        };
+        assert_eq!(
+            call.tokens("Hi, {}!", &[&arg]).to_string(),
+            quote! { crate::ui::__langrustang_alloc::format!("Hi, {}!", name) }.to_string()
+        );
+    }
+
+    #[test]
+    fn feature() {
+        assert_eq!(FormatMacro::Write.check_feature(), Ok(()));
+        assert_eq!(FormatMacro::Panic.check_feature(), Ok(()));
+        assert_eq!(
+            check_feature("plural_t", "alloc", false),
+            Err("`plural_t!` requires the `alloc` feature of langrustang".into())
+        );
+    }
+}
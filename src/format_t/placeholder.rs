@@ -0,0 +1,310 @@
+use std::collections::BTreeSet;
+
+use syn::{Expr, ExprAssign, ExprPath};
+
+use crate::lang_yaml::LocalizedText;
+
+/// format 文字列が必要とする引数の集合
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Placeholders {
+    /// 使われている位置引数の番号
+    pub positional: BTreeSet<usize>,
+    /// 名前付き引数
+    pub named: BTreeSet<String>,
+}
+
+impl Placeholders {
+    /// 必要な位置引数の数 (一番大きい番号 + 1)
+    pub fn positional_count(&self) -> usize {
+        self.positional.last().map_or(0, |i| i + 1)
+    }
+}
+
+impl std::fmt::Display for Placeholders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut items: Vec<String> = self
+            .positional
+            .iter()
+            .map(|i| format!("{{{}}}", i))
+            .collect();
+        items.extend(self.named.iter().map(|name| format!("{{{}}}", name)));
+
+        match items.is_empty() {
+            true => write!(f, "no placeholders"),
+            false => write!(f, "{}", items.join(", ")),
+        }
+    }
+}
+
+/// format 文字列を解析して使われているプレースホルダを取得する
+pub fn parse(template: &str) -> Result<Placeholders, String> {
+    let mut placeholders = Placeholders::default();
+    let indices = &mut placeholders.positional;
+    let mut next_index = 0;
+
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '}' => return Err(format!("unmatched `}}` in {:?}", template)),
+
+            '{' => {
+                let mut inner = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => inner.push(c),
+                        None => return Err(format!("unclosed `{{` in {:?}", template)),
+                    }
+                }
+
+                let (arg, spec) = match inner.split_once(':') {
+                    Some((arg, spec)) => (arg.trim(), spec),
+                    None => (inner.trim(), ""),
+                };
+
+                // `{:.*}` は値の前に精度を次の位置引数から取る
+                if spec.contains(".*") {
+                    indices.insert(next_index);
+                    next_index += 1;
+                }
+
+                match arg {
+                    "" => {
+                        indices.insert(next_index);
+                        next_index += 1;
+                    }
+                    _ => insert_argument(arg, indices, &mut placeholders.named)?,
+                }
+
+                // `{:1$}` や `{:.prec$}` のように幅や精度で参照される引数
+                for (i, c) in spec.char_indices() {
+                    if c != '$' {
+                        continue;
+                    }
+                    let start = spec[..i]
+                        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .map_or(0, |p| p + 1);
+                    let referenced = &spec[start..i];
+                    if referenced.is_empty() {
+                        return Err(format!("invalid format spec in {:?}", template));
+                    }
+                    insert_argument(referenced, indices, &mut placeholders.named)?;
+                }
+            }
+
+            _ => (),
+        }
+    }
+
+    Ok(placeholders)
+}
+
+fn insert_argument(
+    arg: &str,
+    indices: &mut BTreeSet<usize>,
+    named: &mut BTreeSet<String>,
+) -> Result<(), String> {
+    if arg.chars().all(|c| c.is_ascii_digit()) {
+        let index = arg
+            .parse()
+            .map_err(|_| format!("invalid argument index `{}`", arg))?;
+        indices.insert(index);
+        return Ok(());
+    }
+
+    let mut chars = arg.chars();
+    let is_ident = matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+
+    match is_ident {
+        true => {
+            named.insert(arg.to_string());
+            Ok(())
+        }
+        false => Err(format!("invalid argument name `{}`", arg)),
+    }
+}
+
+/// 全ての言語のプレースホルダが一致しているか、渡された引数と一致しているかチェックする
+pub fn check_args(key: &str, localized_text: &LocalizedText, args: &[&Expr]) -> Result<(), String> {
+    let mut templates: Vec<_> = localized_text
+        .iter()
+        .map(|(lang, text)| (lang.clone(), text.as_str()))
+        .collect();
+    templates.sort();
+
+    check_templates(key, &templates, args, &[])
+}
+
+/// (言語, format 文字列) の組のプレースホルダが一致しているか、渡された引数と一致しているかチェックする
+///
+/// `implicit` はマクロが自動で渡す名前付き引数で、使われていなくてもよい
+pub fn check_templates(
+    key: &str,
+    templates: &[(String, &str)],
+    args: &[&Expr],
+    implicit: &[&str],
+) -> Result<(), String> {
+    // 言語ごとのプレースホルダを取得して、使われていない位置引数が無いか確認
+    let mut parsed = vec![];
+    for (lang, template) in templates {
+        let mut placeholders =
+            parse(template).map_err(|err| format!("Key: {}, language `{}`: {}", key, lang, err))?;
+        placeholders
+            .named
+            .retain(|name| !implicit.contains(&name.as_str()));
+
+        if let Some(unused) =
+            (0..placeholders.positional_count()).find(|i| !placeholders.positional.contains(i))
+        {
+            return Err(format!(
+                "Key: {}, language `{}` does not use the positional argument {{{}}}",
+                key, lang, unused
+            ));
+        }
+
+        parsed.push((lang, placeholders));
+    }
+
+    // 一番多くの言語と同じプレースホルダを正しいものとして、違う言語を返す
+    let Some((expected_lang, expected)) = parsed
+        .iter()
+        .max_by_key(|(lang, placeholders)| {
+            let count = parsed.iter().filter(|(_, p)| p == placeholders).count();
+            // 同じ数なら先の言語を使う
+            (count, std::cmp::Reverse(*lang))
+        })
+        .cloned()
+    else {
+        return Ok(());
+    };
+
+    if let Some((lang, placeholders)) = parsed.iter().find(|(_, p)| *p != expected) {
+        return Err(format!(
+            "Key: {}, language `{}` has {} but `{}` has {}",
+            key, lang, placeholders, expected_lang, expected
+        ));
+    }
+
+    // 渡された引数を位置引数と名前付き引数に分ける
+    let mut positional = 0;
+    let mut named = BTreeSet::new();
+    for arg in args {
+        match arg {
+            Expr::Assign(ExprAssign { left, .. }) => match left.as_ref() {
+                Expr::Path(ExprPath { path, .. }) if path.get_ident().is_some() => {
+                    named.insert(path.get_ident().unwrap().to_string());
+                }
+                _ => positional += 1,
+            },
+            _ => positional += 1,
+        }
+    }
+
+    if let Some(name) = implicit.iter().find(|name| named.contains(**name)) {
+        return Err(format!("Key: {} binds `{}` automatically", key, name));
+    }
+
+    if positional != expected.positional_count() {
+        return Err(format!(
+            "Key: {} expects {} positional arguments, but {} were given",
+            key,
+            expected.positional_count(),
+            positional
+        ));
+    }
+
+    if let Some(missing) = expected.named.difference(&named).next() {
+        return Err(format!(
+            "Key: {} requires named argument `{}`",
+            key, missing
+        ));
+    }
+
+    if let Some(unused) = named.difference(&expected.named).next() {
+        return Err(format!("Key: {} has no placeholder `{{{}}}`", key, unused));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn placeholders(positional: usize, named: &[&str]) -> Placeholders {
+        Placeholders {
+            positional: (0..positional).collect(),
+            named: named.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parse_positional() {
+        assert_eq!(parse("{}, {}!").unwrap(), placeholders(2, &[]));
+        assert_eq!(parse("{1} {0}").unwrap(), placeholders(2, &[]));
+        assert_eq!(parse("{:>8} {:.*}").unwrap(), placeholders(3, &[]));
+        assert_eq!(parse("{{}} {{0}}").unwrap(), placeholders(0, &[]));
+    }
+
+    #[test]
+    fn parse_named() {
+        assert_eq!(parse("Hi, {user}!").unwrap(), placeholders(0, &["user"]));
+        assert_eq!(
+            parse("{user:>width$} {:.1$}").unwrap(),
+            placeholders(2, &["user", "width"])
+        );
+    }
+
+    #[test]
+    fn check_unused_index() {
+        let err = check_templates(
+            "key",
+            &[("en".into(), "{0} and {1}"), ("ja".into(), "{1}だけ")],
+            &[],
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "Key: key, language `ja` does not use the positional argument {0}"
+        );
+    }
+
+    #[test]
+    fn check_majority() {
+        // 違うのが en だけなら en を返す
+        let err = check_templates(
+            "key",
+            &[
+                ("en".into(), "{user}"),
+                ("ja".into(), "{name}"),
+                ("zh".into(), "{name}"),
+            ],
+            &[],
+            &[],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            "Key: key, language `en` has {user} but `ja` has {name}"
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("{").unwrap_err().contains("unclosed"));
+        assert!(parse("}").unwrap_err().contains("unmatched"));
+        assert!(parse("{a-b}")
+            .unwrap_err()
+            .contains("invalid argument name"));
+    }
+}
@@ -1,16 +1,28 @@
 pub mod check_yaml;
+#[cfg(feature = "clap")]
+pub mod clap_impl;
+pub mod current_lang;
+pub mod format_args;
+pub mod lang_meta;
+pub mod lookup_table;
+pub mod negotiate;
+pub mod plural_rules;
+pub mod serde_impl;
 pub mod update_yaml;
-
-use std::{collections::HashSet, path::PathBuf};
+pub mod yaml_files;
 
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse::{ParseStream, Parser},
-    Error, Ident, LitStr, Result,
+    braced, bracketed,
+    parse::{Parse, ParseStream, Parser},
+    punctuated::Punctuated,
+    token, Error, Ident, LitBool, LitStr, Result, Token,
 };
 
-use crate::{lang_yaml::LangYaml, YAML_DATA, YAML_LANGS, YAML_PATH};
+use crate::catalog::{CatalogRef, DEFAULT_MODULE};
+
+use yaml_files::YamlFile;
 
 pub fn _i18n(tokens: TokenStream) -> TokenStream {
     i18n_parse
@@ -18,78 +30,175 @@ pub fn _i18n(tokens: TokenStream) -> TokenStream {
         .unwrap_or_else(Error::into_compile_error)
 }
 
-fn i18n_parse(input: ParseStream) -> Result<TokenStream> {
-    let literal: LitStr = input.parse()?;
-    let input_filepath = literal.value();
-
-    let err_return = |s: String| Err(Error::new(literal.span(), s));
+/// i18n! の引数
+pub struct I18nArgs {
+    /// `name = ui` で指定された、生成するモジュールの名前
+    pub name: Option<Ident>,
+    /// yaml のパス
+    pub literals: Vec<LitStr>,
+    /// `fallback = { "pt_br": "pt" }` で指定された、言語ごとのフォールバック先
+    pub fallback: Vec<(LitStr, Vec<LitStr>)>,
+    /// `default = "en"` で指定された、デフォルトの言語
+    pub default: Option<LitStr>,
+    /// `serde = true` で、Lang に Serialize と Deserialize を実装する
+    pub serde: bool,
+}
 
-    // yaml かどうか確認
-    if !input_filepath.ends_with(".yaml") {
-        return err_return("expected .yaml file path".into());
-    }
+impl Parse for I18nArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut name = None;
+        let mut literals = None;
+        let mut fallback = vec![];
+        let mut default = None;
+        let mut serde = false;
+
+        loop {
+            match input.peek(Ident) && input.peek2(Token![=]) {
+                // option = value
+                true => {
+                    let option: Ident = input.parse()?;
+                    input.parse::<Token![=]>()?;
+
+                    match option.to_string().as_str() {
+                        "name" => name = Some(input.parse()?),
+                        "fallback" => fallback = parse_fallback(input)?,
+                        "default" => default = Some(input.parse()?),
+                        "serde" => serde = input.parse::<LitBool>()?.value,
+                        _ => {
+                            return Err(Error::new(
+                                option.span(),
+                                format!("Unknown option: `{}`", option),
+                            ))
+                        }
+                    }
+                }
 
-    // ファイルが存在するか確認
-    let pathbuf = PathBuf::from(&input_filepath);
-    let display_path = pathbuf.canonicalize().unwrap_or_else(|_| pathbuf.clone());
+                // 1つのパス、または [...] で囲んだパスのリスト
+                false => {
+                    let span = input.span();
+                    let paths: Vec<LitStr> = match input.peek(token::Bracket) {
+                        true => {
+                            let content;
+                            bracketed!(content in input);
+                            Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+                                .into_iter()
+                                .collect()
+                        }
+                        false => vec![input.parse()?],
+                    };
 
-    if !pathbuf.exists() {
-        return err_return(format!("File is not found: {:?}", display_path));
-    }
+                    if literals.replace(paths).is_some() {
+                        return Err(Error::new(span, "The yaml path is specified twice"));
+                    }
+                }
+            }
 
-    // yamlパスの設定
-    {
-        let mut lock = YAML_PATH.lock().unwrap();
-        *lock = input_filepath.clone();
-    }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+        }
 
-    // yaml を読み込み
-    let yaml_string = match std::fs::read_to_string(&input_filepath) {
-        Ok(s) => s,
-        Err(_) => return err_return(format!("Failed to open file: {:?}", display_path)),
-    };
+        let literals = literals.unwrap_or_default();
+        if literals.is_empty() {
+            return Err(Error::new(input.span(), "expected string literal"));
+        }
 
-    // yaml への変換と static への保存
-    let lang_yaml = {
-        let mut lock = YAML_DATA.lock().unwrap();
+        Ok(Self {
+            name,
+            literals,
+            fallback,
+            default,
+            serde,
+        })
+    }
+}
 
-        let yaml: LangYaml = match serde_yaml::from_str(&yaml_string) {
-            Ok(yaml) => yaml,
-            Err(_) => {
-                return err_return(format!("Failed to parse yaml"));
+/// `{ "pt_br": "pt", "pt": ["es", "en"] }`
+fn parse_fallback(input: ParseStream) -> Result<Vec<(LitStr, Vec<LitStr>)>> {
+    let content;
+    braced!(content in input);
+
+    let mut fallback = vec![];
+    while !content.is_empty() {
+        let lang: LitStr = content.parse()?;
+        content.parse::<Token![:]>()?;
+
+        let targets = match content.peek(token::Bracket) {
+            true => {
+                let targets;
+                bracketed!(targets in content);
+                Punctuated::<LitStr, Token![,]>::parse_terminated(&targets)?
+                    .into_iter()
+                    .collect()
             }
+            false => vec![content.parse()?],
         };
+        fallback.push((lang, targets));
 
-        *lock = Some(yaml.clone());
-        yaml
-    };
+        if content.is_empty() {
+            break;
+        }
+        content.parse::<Token![,]>()?;
+    }
 
-    // rust の enum に使える文字列かチェック
-    if let Err(err) = check_yaml::check_yaml(&lang_yaml) {
-        return err_return(err);
-    };
+    Ok(fallback)
+}
 
-    // all を除いてどの言語キーが使われているか取得
-    let mut langs_set = HashSet::new();
-    for (_, localized) in lang_yaml.iter() {
-        for (lang, _) in localized.iter() {
-            if lang.to_ascii_lowercase() != "all" {
-                langs_set.insert(lang.clone());
+impl I18nArgs {
+    pub fn span(&self) -> Span {
+        self.literals[0].span()
+    }
+
+    /// 生成するモジュールの名前
+    pub fn module(&self) -> Ident {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => Ident::new(DEFAULT_MODULE, Span::call_site()),
+        }
+    }
+
+    /// 読み込む yaml ファイルを集める
+    pub fn files(&self) -> Result<Vec<YamlFile>> {
+        let mut files = vec![];
+        for literal in self.literals.iter() {
+            match yaml_files::collect(&literal.value()) {
+                Ok(collected) => files.extend(collected),
+                Err(err) => return Err(Error::new(literal.span(), err)),
             }
         }
+
+        Ok(files)
     }
+}
 
-    {
-        let mut lock = YAML_LANGS.lock().unwrap();
-        *lock = langs_set.clone()
-    };
+fn i18n_parse(input: ParseStream) -> Result<TokenStream> {
+    // lang_t! などに渡すために、引数のトークンをそのまま取っておく
+    let args_tokens: TokenStream = input.fork().parse()?;
+    let args: I18nArgs = input.parse()?;
+
+    let yaml_files = args.files()?;
+    let catalog = CatalogRef::load(&args)?;
+    let module = args.module();
+
+    let input_paths: Vec<_> = args
+        .literals
+        .iter()
+        .map(|literal| literal.value())
+        .collect();
+
+    // enum の要素の順番 (enum の名前順) にした言語キー
+    let mut yaml_langs: Vec<_> = catalog.sorted_langs().into_iter().cloned().collect();
+    yaml_langs.sort_by_key(|lang| check_yaml::to_enumval_format(lang));
 
     // enum の命名規則にする
-    let mut langs = vec![];
-    for i in langs_set {
-        langs.push(check_yaml::to_enumval_format(&i));
-    }
-    langs.sort();
+    let langs: Vec<_> = yaml_langs
+        .iter()
+        .map(|lang| check_yaml::to_enumval_format(lang))
+        .collect();
 
     // 言語キーを ident に変換
     let langs_ident: Vec<Ident> = langs
@@ -97,18 +206,18 @@ fn i18n_parse(input: ParseStream) -> Result<TokenStream> {
         .map(|s| Ident::new(s, Span::call_site()))
         .collect();
 
-    // std::fmt::Display
+    // core::fmt::Display
     let display_match_arms: Vec<_> = langs_ident
         .iter()
         .zip(langs.iter())
         .map(|(ident, lang)| {
             quote! {
-                #ident => write!(f, #lang ),
+                Lang::#ident => ::core::write!(f, #lang),
             }
         })
         .collect();
 
-    // std::str::FromStr
+    // core::str::FromStr
     let fromstr_match_arms: Vec<_> = langs_ident
         .iter()
         .zip(langs.iter())
@@ -117,50 +226,205 @@ fn i18n_parse(input: ParseStream) -> Result<TokenStream> {
             let apper = lang.to_ascii_uppercase();
 
             quote! {
-                #lang => Ok( #ident ),
-                #lower => Ok( #ident ),
-                #apper => Ok( #ident ),
+                #lang => ::core::result::Result::Ok(Lang::#ident),
+                #lower => ::core::result::Result::Ok(Lang::#ident),
+                #apper => ::core::result::Result::Ok(Lang::#ident),
+            }
+        })
+        .collect();
+
+    let lang_count = langs.len();
+
+    // yaml に書かれた言語キー
+    let as_str_match_arms: Vec<_> = langs_ident
+        .iter()
+        .zip(yaml_langs.iter())
+        .map(|(ident, lang)| {
+            quote! {
+                Lang::#ident => #lang,
+            }
+        })
+        .collect();
+
+    // デフォルトの言語が指定されていれば Default を実装する
+    let default_impl = catalog.default.as_ref().map(|default| {
+        let ident = Ident::new(&check_yaml::to_enumval_format(default), Span::call_site());
+
+        quote! {
+            impl ::core::default::Default for Lang {
+                fn default() -> Self {
+                    Lang::#ident
+                }
             }
+        }
+    });
+
+    // yaml が変更されたら再コンパイルされるように、読み込んだファイルを include_bytes! しておく
+    let include_paths: Vec<_> = yaml_files
+        .iter()
+        .map(|file| {
+            let path = file.path();
+            let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            path.to_string_lossy().into_owned()
         })
         .collect();
 
-    let auto_gen_by = {
-        let lock = YAML_PATH.lock().unwrap();
-        format!("Auto-generated by `langrustang::i18n!` from `{}`", lock)
+    let auto_gen_by = format!(
+        "Auto-generated by `langrustang::i18n!` from `{}`",
+        input_paths.join("`, `")
+    );
+
+    // 複数形のルール
+    let plural = plural_rules::plural_tokens(&yaml_langs);
+
+    // 実行時にキーから文字列を探す関数
+    let lookup = lookup_table::lookup_tokens(&catalog, &yaml_langs);
+
+    // 言語の名前、BCP 47 タグ、文字の方向
+    let meta = lang_meta::meta_tokens(&catalog, &yaml_langs);
+
+    // ロケールから言語を選ぶ関数
+    let negotiate = negotiate::negotiate_tokens();
+
+    // serde の Serialize と Deserialize
+    let serde = args.serde.then(|| serde_impl::serde_tokens(&yaml_langs));
+
+    // 現在の言語
+    let current = current_lang::current_lang_tokens(catalog.default.as_deref());
+
+    // format_args_t! が返す値
+    let format_args = format_args::format_args_tokens();
+
+    // format_t! などが no_std のクレートでも使えるように alloc を読み込んでおく
+    #[cfg(feature = "alloc")]
+    let alloc = quote! {
+        #[doc(hidden)]
+        pub extern crate alloc as __langrustang_alloc;
     };
+    #[cfg(not(feature = "alloc"))]
+    let alloc = TokenStream::new();
+
+    // clap の ValueEnum
+    #[cfg(feature = "clap")]
+    let clap = clap_impl::clap_tokens();
+    #[cfg(not(feature = "clap"))]
+    let clap = TokenStream::new();
+
+    // Accept-Language から言語を選ぶ関数
+    #[cfg(feature = "accept-language")]
+    let accept_language = negotiate::accept_language_tokens(catalog.default.as_deref());
+    #[cfg(not(feature = "accept-language"))]
+    let accept_language = TokenStream::new();
 
     Ok(quote! {
         #[doc = #auto_gen_by]
-        pub mod _langrustang_autogen {
+        pub mod #module {
+            #( const _: &[u8] = ::core::include_bytes!(#include_paths); )*
+
+            #alloc
+
+            // `lang_t!` などに、この yaml のパスを渡して呼び出し直す
+            #[doc(hidden)]
+            macro_rules! __langrustang_catalog {
+                ($macro_name:ident ! { $($tokens:tt)* }) => {
+                    ::langrustang::$macro_name! { @catalog ( #args_tokens ) $($tokens)* }
+                };
+            }
+
+            #[doc(hidden)]
+            pub(crate) use __langrustang_catalog;
 
             #[doc = #auto_gen_by]
-            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[derive(
+                ::core::fmt::Debug,
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::cmp::PartialOrd,
+                ::core::cmp::Ord,
+                ::core::hash::Hash,
+            )]
             pub enum Lang {
                 #(#langs_ident),* ,
             }
 
-            impl std::fmt::Display for Lang {
-                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    use Lang::*;
+            impl Lang {
+                /// Every language, in the order of the variants.
+                pub const ALL: [Lang; #lang_count] = [#(Lang::#langs_ident),*];
 
+                /// The number of languages.
+                pub const COUNT: usize = #lang_count;
+
+                /// Returns an iterator over every language, in the order of the variants.
+                pub fn iter() -> ::core::array::IntoIter<Lang, #lang_count> {
+                    ::core::iter::IntoIterator::into_iter(Self::ALL)
+                }
+
+                /// Returns the language key written in the yaml (e.g. `some_key_2`).
+                pub const fn as_str(self) -> &'static str {
                     match self {
-                        #(#display_match_arms)*
+                        #(#as_str_match_arms)*
                     }
                 }
             }
 
-            impl std::str::FromStr for Lang {
-                type Err = String;
-                fn from_str(s: &str) -> Result<Self, Self::Err> {
-                    use Lang::*;
+            #default_impl
+
+            impl ::core::fmt::Display for Lang {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        #(#display_match_arms)*
+                    }
+                }
+            }
 
+            impl ::core::str::FromStr for Lang {
+                type Err = ParseLangError;
+                fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
                     match s {
                         #(#fromstr_match_arms)*
-                        _ => Err(format!("Unknown option: `{}`", s)),
+                        _ => ::core::result::Result::Err(ParseLangError(())),
                     }
                 }
             }
 
+            /// The error returned when parsing a string that is not a language key.
+            #[derive(
+                ::core::fmt::Debug,
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
+            )]
+            pub struct ParseLangError(());
+
+            impl ::core::fmt::Display for ParseLangError {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str("unknown language key")
+                }
+            }
+
+            impl ::core::error::Error for ParseLangError {}
+
+            #meta
+
+            #negotiate
+
+            #accept_language
+
+            #serde
+
+            #clap
+
+            #current
+
+            #format_args
+
+            #plural
+
+            #lookup
         }
     })
 }
@@ -182,12 +446,64 @@ mod tests {
     #[test]
     fn test_i18n_ok() {
         let token1 = _i18n(quote! { "files/test_file.yaml" }).to_string();
+        let plural = plural_rules::plural_tokens(&["en", "ja", "test1", "zh"].map(String::from));
+        let include_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("files/test_file.yaml")
+            .canonicalize()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        let catalog = CatalogRef::load(&syn::parse_quote! { "files/test_file.yaml" }).unwrap();
+        let langs = ["en", "ja", "test1", "zh"].map(String::from);
+        let meta = lang_meta::meta_tokens(&catalog, &langs);
+        let lookup = lookup_table::lookup_tokens(&catalog, &langs);
+        let negotiate = negotiate::negotiate_tokens();
+        #[cfg(feature = "accept-language")]
+        let accept_language = negotiate::accept_language_tokens(None);
+        #[cfg(not(feature = "accept-language"))]
+        let accept_language = TokenStream::new();
+        #[cfg(feature = "clap")]
+        let clap = clap_impl::clap_tokens();
+        #[cfg(not(feature = "clap"))]
+        let clap = TokenStream::new();
+        let current = current_lang::current_lang_tokens(None);
+        let format_args = format_args::format_args_tokens();
+        #[cfg(feature = "alloc")]
+        let alloc = quote! {
+            #[doc(hidden)]
+            pub extern crate alloc as __langrustang_alloc;
+        };
+        #[cfg(not(feature = "alloc"))]
+        let alloc = TokenStream::new();
         let token2 = quote! {
             #[doc = "Auto-generated by `langrustang::i18n!` from `files/test_file.yaml`"]
             pub mod _langrustang_autogen {
+                const _: &[u8] = ::core::include_bytes!(#include_path);
+
+                #alloc
+
+                // `lang_t!` などに、この yaml のパスを渡して呼び出し直す
+                #[doc(hidden)]
+                macro_rules! __langrustang_catalog {
+                    ($macro_name:ident ! { $($tokens:tt)* }) => {
+                        ::langrustang::$macro_name! { @catalog ( "files/test_file.yaml" ) $($tokens)* }
+                    };
+                }
+
+                #[doc(hidden)]
+                pub(crate) use __langrustang_catalog;
 
                 #[doc = "Auto-generated by `langrustang::i18n!` from `files/test_file.yaml`"]
-                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                #[derive(
+                    ::core::fmt::Debug,
+                    ::core::clone::Clone,
+                    ::core::marker::Copy,
+                    ::core::cmp::PartialEq,
+                    ::core::cmp::Eq,
+                    ::core::cmp::PartialOrd,
+                    ::core::cmp::Ord,
+                    ::core::hash::Hash,
+                )]
                 pub enum Lang {
                     En,
                     Ja,
@@ -195,42 +511,96 @@ mod tests {
                     Zh,
                 }
 
-                impl std::fmt::Display for Lang {
-                    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                        use Lang::*;
+                impl Lang {
+                    /// Every language, in the order of the variants.
+                    pub const ALL: [Lang; 4usize] = [Lang::En, Lang::Ja, Lang::Test1, Lang::Zh];
 
+                    /// The number of languages.
+                    pub const COUNT: usize = 4usize;
+
+                    /// Returns an iterator over every language, in the order of the variants.
+                    pub fn iter() -> ::core::array::IntoIter<Lang, 4usize> {
+                        ::core::iter::IntoIterator::into_iter(Self::ALL)
+                    }
+
+                    /// Returns the language key written in the yaml (e.g. `some_key_2`).
+                    pub const fn as_str(self) -> &'static str {
                         match self {
-                            En => write!(f, "En"),
-                            Ja => write!(f, "Ja"),
-                            Test1 => write!(f, "Test1"),
-                            Zh => write!(f, "Zh"),
+                            Lang::En => "en",
+                            Lang::Ja => "ja",
+                            Lang::Test1 => "test1",
+                            Lang::Zh => "zh",
                         }
                     }
                 }
 
-                impl std::str::FromStr for Lang {
-                    type Err = String;
-                    fn from_str(s: &str) -> Result<Self, Self::Err> {
-                        use Lang::*;
+                impl ::core::fmt::Display for Lang {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self {
+                            Lang::En => ::core::write!(f, "En"),
+                            Lang::Ja => ::core::write!(f, "Ja"),
+                            Lang::Test1 => ::core::write!(f, "Test1"),
+                            Lang::Zh => ::core::write!(f, "Zh"),
+                        }
+                    }
+                }
 
+                impl ::core::str::FromStr for Lang {
+                    type Err = ParseLangError;
+                    fn from_str(s: &str) -> ::core::result::Result<Self, Self::Err> {
                         match s {
-                            "En" => Ok(En),
-                            "en" => Ok(En),
-                            "EN" => Ok(En),
-                            "Ja" => Ok(Ja),
-                            "ja" => Ok(Ja),
-                            "JA" => Ok(Ja),
-                            "Test1" => Ok(Test1),
-                            "test1" => Ok(Test1),
-                            "TEST1" => Ok(Test1),
-                            "Zh" => Ok(Zh),
-                            "zh" => Ok(Zh),
-                            "ZH" => Ok(Zh),
-
-                            _ => Err(format!("Unknown option: `{}`", s)),
+                            "En" => ::core::result::Result::Ok(Lang::En),
+                            "en" => ::core::result::Result::Ok(Lang::En),
+                            "EN" => ::core::result::Result::Ok(Lang::En),
+                            "Ja" => ::core::result::Result::Ok(Lang::Ja),
+                            "ja" => ::core::result::Result::Ok(Lang::Ja),
+                            "JA" => ::core::result::Result::Ok(Lang::Ja),
+                            "Test1" => ::core::result::Result::Ok(Lang::Test1),
+                            "test1" => ::core::result::Result::Ok(Lang::Test1),
+                            "TEST1" => ::core::result::Result::Ok(Lang::Test1),
+                            "Zh" => ::core::result::Result::Ok(Lang::Zh),
+                            "zh" => ::core::result::Result::Ok(Lang::Zh),
+                            "ZH" => ::core::result::Result::Ok(Lang::Zh),
+
+                            _ => ::core::result::Result::Err(ParseLangError(())),
                         }
                     }
                 }
+
+                /// The error returned when parsing a string that is not a language key.
+                #[derive(
+                    ::core::fmt::Debug,
+                    ::core::clone::Clone,
+                    ::core::marker::Copy,
+                    ::core::cmp::PartialEq,
+                    ::core::cmp::Eq,
+                    ::core::hash::Hash,
+                )]
+                pub struct ParseLangError(());
+
+                impl ::core::fmt::Display for ParseLangError {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str("unknown language key")
+                    }
+                }
+
+                impl ::core::error::Error for ParseLangError {}
+
+                #meta
+
+                #negotiate
+
+                #accept_language
+
+                #clap
+
+                #current
+
+                #format_args
+
+                #plural
+
+                #lookup
             }
         }
         .to_string();
@@ -238,6 +608,63 @@ mod tests {
         assert_eq!(token1, token2);
     }
 
+    #[test]
+    fn named_catalog() {
+        let token = _i18n(quote! { name = ui, "files/test_file.yaml" }).to_string();
+
+        assert!(token.contains(&quote! { pub mod ui }.to_string()));
+        assert!(token.contains(
+            &quote! { ::langrustang::$macro_name! { @catalog ( name = ui, "files/test_file.yaml" ) $($tokens)* } }
+                .to_string()
+        ));
+
+        let token = _i18n(quote! { module = ui, "files/test_file.yaml" }).to_string();
+        assert!(token.contains("Unknown option: `module`"));
+    }
+
+    #[test]
+    fn lang_helpers() {
+        let token = _i18n(quote! { "files/fallback.yaml" }).to_string();
+
+        assert!(token.contains(
+            &quote! { pub const ALL: [Lang; 4usize] = [Lang::En, Lang::Ja, Lang::Pt, Lang::PtBr]; }
+                .to_string()
+        ));
+        assert!(token.contains(&quote! { Lang::PtBr => "pt_br", }.to_string()));
+    }
+
+    #[test]
+    fn default_lang() {
+        let token = _i18n(quote! { "files/fallback.yaml", default = "en" }).to_string();
+        assert!(token.contains(
+            &quote! { impl ::core::default::Default for Lang { fn default() -> Self { Lang::En } } }.to_string()
+        ));
+
+        // yaml の _default
+        let token = _i18n(quote! { "files/default.yaml" }).to_string();
+        assert!(token.contains(
+            &quote! { impl ::core::default::Default for Lang { fn default() -> Self { Lang::Ja } } }.to_string()
+        ));
+
+        let token = _i18n(quote! {
+            "files/fallback.yaml", fallback = { "pt_br": [] }, default = "pt_br"
+        })
+        .to_string();
+        assert!(token.contains("Key: bye has no value for the default language `pt_br`"));
+
+        let token = _i18n(quote! { "files/fallback.yaml", default = "fr" }).to_string();
+        assert!(token.contains("Unknown default language: `fr`"));
+    }
+
+    #[test]
+    fn serde_option() {
+        let token = _i18n(quote! { "files/test_file.yaml", serde = true }).to_string();
+        assert!(token.contains(&quote! { impl ::serde::Serialize for Lang }.to_string()));
+
+        let token = _i18n(quote! { "files/test_file.yaml", serde = false }).to_string();
+        assert!(!token.contains(&quote! { impl ::serde::Serialize for Lang }.to_string()));
+    }
+
     #[test]
     fn check_no_exists() {
         let token = _i18n(quote! { "../files/no_exists.yaml" }).to_string();
@@ -250,6 +677,15 @@ mod tests {
         assert!(token.contains("expected .yaml file path"));
     }
 
+    #[test]
+    fn check_list() {
+        let token = _i18n(quote! { ["files/test_file.yaml", "files/no_exists/"] }).to_string();
+        assert!(token.contains("Directory is not found: ") && token.contains("files/no_exists"));
+
+        let token = _i18n(quote! { [] }).to_string();
+        assert!(token.contains("expected string literal"));
+    }
+
     #[test]
     fn check_literal() {
         let token = _i18n(quote! { not_literal }).to_string();
@@ -1,50 +1,44 @@
-use std::collections::HashSet;
-
-use super::LangYaml;
+use crate::lang_yaml::LangYaml;
 
 /// rust の enum に使える文字列かチェック
 pub fn check_yaml(yaml: &LangYaml) -> Result<(), String> {
-    let mut lang_keys = HashSet::new();
-
     for (_, localized) in yaml.iter() {
-        for (lang, _) in localized.iter() {
-            // 1文字目が ascii alphabet 以外じゃないかどうか確かめておく
-            let mut chars = lang.chars();
-
-            match chars.nth(0) {
-                Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
-                Some(_) => return Err("The first character of the language key contains something other than ascii_alphabet.".into()),
-                None => return Err("Failed to get char".into()),
-            };
-
-            // 残りの文字が ascii alphabet, または ascii numeric または数字、アンダースコか確かめておく
-            for c in chars {
-                if !(c.is_ascii_alphanumeric() || c == '_') {
-                    return Err("Language keys cannot be anything other than ascii_alphabet or ascii_discrit."
-                                            .into());
-                }
-            }
+        for lang in localized.langs() {
+            check_lang(lang)?;
+        }
+    }
 
-            // すべて小文字、または数字、アンダースコアかチェック
-            let mut chars = lang.chars();
-            let is_disit_and_lowercase =
-                chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    // フォールバックで指定された言語キーもチェック
+    for (lang, targets) in yaml.fallback.iter() {
+        check_lang(lang)?;
+        for target in targets {
+            check_lang(target)?;
+        }
+    }
 
-            if !is_disit_and_lowercase {
-                return Err("The language key must be in all lowercase.".into());
-            }
+    if let Some(default) = &yaml.default {
+        check_lang(default)?;
+    }
 
-            // 最後の文字がアンダースコアでないかチェック
-            let mut chars = lang.chars().rev();
-            if let Some('_') = chars.next() {
-                return Err(
-                    "The last character of the language key cannot be an underscore.".into(),
-                );
+    // 言語の情報をチェック
+    for (lang, meta) in yaml.meta.iter() {
+        check_lang(lang)?;
+
+        if let Some(tag) = &meta.tag {
+            if !is_bcp47(tag) {
+                return Err(format!(
+                    "Metadata of `{}`: `{}` is not a valid BCP 47 tag",
+                    lang, tag
+                ));
             }
+        }
 
-            // all は除外する
-            if lang != "all" {
-                lang_keys.insert(lang);
+        if let Some(dir) = &meta.dir {
+            if dir != "ltr" && dir != "rtl" {
+                return Err(format!(
+                    "Metadata of `{}`: `dir` must be `ltr` or `rtl`, but got `{}`",
+                    lang, dir
+                ));
             }
         }
     }
@@ -52,6 +46,64 @@ pub fn check_yaml(yaml: &LangYaml) -> Result<(), String> {
     Ok(())
 }
 
+/// 言語キーが rust の enum に使える文字列かチェック
+pub fn check_lang(lang: &str) -> Result<(), String> {
+    // 1文字目が ascii alphabet 以外じゃないかどうか確かめておく
+    let mut chars = lang.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+        Some(_) => return Err(
+            "The first character of the language key contains something other than ascii_alphabet."
+                .into(),
+        ),
+        None => return Err("Failed to get char".into()),
+    };
+
+    // 残りの文字が ascii alphabet, または ascii numeric または数字、アンダースコか確かめておく
+    for c in chars {
+        if !(c.is_ascii_alphanumeric() || c == '_') {
+            return Err(
+                "Language keys cannot be anything other than ascii_alphabet or ascii_discrit."
+                    .into(),
+            );
+        }
+    }
+
+    // すべて小文字、または数字、アンダースコアかチェック
+    let mut chars = lang.chars();
+    let is_disit_and_lowercase =
+        chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if !is_disit_and_lowercase {
+        return Err("The language key must be in all lowercase.".into());
+    }
+
+    // 最後の文字がアンダースコアでないかチェック
+    let mut chars = lang.chars().rev();
+    if let Some('_') = chars.next() {
+        return Err("The last character of the language key cannot be an underscore.".into());
+    }
+
+    Ok(())
+}
+
+/// BCP 47 タグの形になっているか (`ja`, `zh-Hant-TW`, `es-419` など)
+///
+/// 最初のサブタグは2から3文字か5から8文字のアルファベット、それ以降は1から8文字の英数字
+pub fn is_bcp47(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+
+    let is_language = subtags.next().is_some_and(|language| {
+        matches!(language.len(), 2..=3 | 5..=8) && language.chars().all(|c| c.is_ascii_alphabetic())
+    });
+
+    is_language
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
 /// 1文字目は大文字、それ以降は小文字に変換する、アンダーバーがあればその次の文字を大文字にする
 pub fn to_enumval_format(text: &str) -> String {
     let mut chars = text.chars();
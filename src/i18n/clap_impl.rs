@@ -0,0 +1,19 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// `clap` の feature が有効な時に、yaml の言語キーを値にして、言語の名前をヘルプにする ValueEnum を生成する
+pub fn clap_tokens() -> TokenStream {
+    quote! {
+        impl ::clap::ValueEnum for Lang {
+            fn value_variants<'a>() -> &'a [Self] {
+                &Lang::ALL
+            }
+
+            fn to_possible_value(&self) -> ::core::option::Option<::clap::builder::PossibleValue> {
+                ::core::option::Option::Some(
+                    ::clap::builder::PossibleValue::new(self.as_str()).help(self.native_name()),
+                )
+            }
+        }
+    }
+}
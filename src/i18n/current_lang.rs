@@ -0,0 +1,78 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use super::check_yaml;
+
+/// `set_current_lang` と `current_lang`、スレッドごとに上書きする `set_thread_lang` (std のみ) を生成する
+///
+/// 最初の言語はデフォルトの言語、無ければ最初の言語
+pub fn current_lang_tokens(default: Option<&str>) -> TokenStream {
+    let initial = match default {
+        Some(default) => {
+            let ident = Ident::new(&check_yaml::to_enumval_format(default), Span::call_site());
+            quote! { Lang::#ident as usize }
+        }
+        None => quote! { 0 },
+    };
+
+    // スレッドごとの上書きは std の時だけ使える
+    #[cfg(feature = "std")]
+    let thread_lang = quote! {
+        ::std::thread_local! {
+            // このスレッドだけで上書きした言語
+            static THREAD_LANG: ::core::cell::Cell<::core::option::Option<Lang>> = const { ::core::cell::Cell::new(::core::option::Option::None) };
+        }
+
+        /// Overrides the current language in this thread until the returned guard is dropped.
+        pub fn set_thread_lang(lang: Lang) -> LangGuard {
+            LangGuard {
+                previous: THREAD_LANG.with(|current| current.replace(::core::option::Option::Some(lang))),
+                _not_send: ::core::marker::PhantomData,
+            }
+        }
+
+        /// Restores the previous language of this thread when dropped.
+        #[must_use = "the language is restored as soon as the guard is dropped"]
+        pub struct LangGuard {
+            previous: ::core::option::Option<Lang>,
+            _not_send: ::core::marker::PhantomData<*const ()>,
+        }
+
+        impl ::core::ops::Drop for LangGuard {
+            fn drop(&mut self) {
+                THREAD_LANG.with(|current| current.set(self.previous));
+            }
+        }
+    };
+    #[cfg(not(feature = "std"))]
+    let thread_lang = TokenStream::new();
+
+    #[cfg(feature = "std")]
+    let current = quote! {
+        THREAD_LANG
+            .with(|lang| lang.get())
+            .unwrap_or_else(|| Lang::ALL[CURRENT_LANG.load(::core::sync::atomic::Ordering::Relaxed)])
+    };
+    #[cfg(not(feature = "std"))]
+    let current = quote! {
+        Lang::ALL[CURRENT_LANG.load(::core::sync::atomic::Ordering::Relaxed)]
+    };
+
+    quote! {
+        // 全てのスレッドの現在の言語 (`Lang::ALL` の番号)
+        static CURRENT_LANG: ::core::sync::atomic::AtomicUsize = ::core::sync::atomic::AtomicUsize::new(#initial);
+
+        /// Sets the language used by `@current` (e.g. `lang_t!("key", @current)`) in every thread.
+        pub fn set_current_lang(lang: Lang) {
+            CURRENT_LANG.store(lang as usize, ::core::sync::atomic::Ordering::Relaxed);
+        }
+
+        /// Returns the language of this thread set by `set_thread_lang`, or else the one set by `set_current_lang`.
+        pub fn current_lang() -> Lang {
+            #current
+        }
+
+        #thread_lang
+    }
+}
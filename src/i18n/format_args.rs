@@ -0,0 +1,43 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// format_args_t! が返す、確保せずにフォーマットする `FormatArgs` を生成する
+pub fn format_args_tokens() -> TokenStream {
+    quote! {
+        /// A localized message returned by `langrustang::format_args_t!`.
+        ///
+        /// It borrows the arguments and formats the text in `Display` without allocating a `String`.
+        pub struct FormatArgs<A, F> {
+            args: A,
+            fmt: F,
+        }
+
+        impl<A, F> FormatArgs<A, F>
+        where
+            F: ::core::ops::Fn(&A, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+        {
+            #[doc(hidden)]
+            pub fn new(args: A, fmt: F) -> Self {
+                Self { args, fmt }
+            }
+        }
+
+        impl<A, F> ::core::fmt::Display for FormatArgs<A, F>
+        where
+            F: ::core::ops::Fn(&A, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                (self.fmt)(&self.args, f)
+            }
+        }
+
+        impl<A, F> ::core::fmt::Debug for FormatArgs<A, F>
+        where
+            F: ::core::ops::Fn(&A, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                (self.fmt)(&self.args, f)
+            }
+        }
+    }
+}
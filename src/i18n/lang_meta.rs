@@ -0,0 +1,164 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use super::check_yaml;
+use crate::catalog::CatalogRef;
+
+/// 通常は右から左に書く言語
+const RTL_LANGS: [&str; 12] = [
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "ug", "ur",
+];
+
+/// `_meta` から `native_name`, `bcp47`, `direction` を生成する
+///
+/// `langs` は `Lang` の要素の順番
+pub fn meta_tokens(catalog: &CatalogRef, langs: &[String]) -> TokenStream {
+    let mut name_arms = vec![];
+    let mut tag_arms = vec![];
+    let mut dir_arms = vec![];
+
+    for lang in langs {
+        let ident = Ident::new(&check_yaml::to_enumval_format(lang), Span::call_site());
+        let meta = catalog.yaml.meta.get(lang).cloned().unwrap_or_default();
+
+        let name = meta.name.unwrap_or_else(|| lang.clone());
+        let tag = meta.tag.unwrap_or_else(|| default_tag(lang));
+        let dir = match meta.dir {
+            Some(dir) => dir == "rtl",
+            None => is_rtl(&tag),
+        };
+        let dir = match dir {
+            true => quote! { Direction::Rtl },
+            false => quote! { Direction::Ltr },
+        };
+
+        name_arms.push(quote! { Lang::#ident => #name, });
+        tag_arms.push(quote! { Lang::#ident => #tag, });
+        dir_arms.push(quote! { Lang::#ident => #dir, });
+    }
+
+    quote! {
+        /// The direction in which the text of a language is written.
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        pub enum Direction {
+            Ltr,
+            Rtl,
+        }
+
+        impl Lang {
+            /// Returns the name of the language in itself (`_meta.<lang>.name`), or the yaml key if it is not given.
+            pub const fn native_name(self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
+
+            /// Returns the BCP 47 tag (`_meta.<lang>.tag`), or the tag made from the yaml key (e.g. `zh_tw` is `zh-TW`).
+            pub const fn bcp47(self) -> &'static str {
+                match self {
+                    #(#tag_arms)*
+                }
+            }
+
+            /// Returns the direction of the text (`_meta.<lang>.dir`), or the usual direction of the language.
+            pub const fn direction(self) -> Direction {
+                match self {
+                    #(#dir_arms)*
+                }
+            }
+        }
+    }
+}
+
+/// yaml の言語キーから BCP 47 タグを作る
+///
+/// `zh_tw` は `zh-TW`、`zh_hant_tw` は `zh-Hant-TW` のように、4文字は文字体系、2文字と3桁の数字は地域にする
+pub fn default_tag(lang: &str) -> String {
+    let mut subtags = lang.split('_');
+    let mut tag = subtags.next().unwrap_or_default().to_string();
+
+    for subtag in subtags {
+        tag.push('-');
+
+        let is_region = (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic()))
+            || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()));
+        let is_script = subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic());
+
+        match (is_region, is_script) {
+            (true, _) => tag.push_str(&subtag.to_ascii_uppercase()),
+            (_, true) => {
+                tag.push_str(&subtag[..1].to_ascii_uppercase());
+                tag.push_str(&subtag[1..]);
+            }
+            _ => tag.push_str(subtag),
+        }
+    }
+
+    tag
+}
+
+/// BCP 47 タグの言語が右から左に書く言語か
+fn is_rtl(tag: &str) -> bool {
+    let language = tag.split('-').next().unwrap_or_default();
+    RTL_LANGS.contains(&language.to_ascii_lowercase().as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn tags() {
+        assert_eq!(default_tag("en"), "en");
+        assert_eq!(default_tag("zh_tw"), "zh-TW");
+        assert_eq!(default_tag("zh_hant_tw"), "zh-Hant-TW");
+        assert_eq!(default_tag("es_419"), "es-419");
+        assert_eq!(default_tag("test1"), "test1");
+
+        assert!(check_yaml::is_bcp47("zh-Hant-TW"));
+        assert!(check_yaml::is_bcp47("es-419"));
+        assert!(!check_yaml::is_bcp47("ja_JP"));
+        assert!(!check_yaml::is_bcp47("j"));
+    }
+
+    #[test]
+    fn meta() {
+        let catalog = CatalogRef::load(&parse_quote! { "files/meta.yaml" }).unwrap();
+        let langs = ["ar", "en", "ja", "zh_tw"].map(String::from);
+
+        let token = meta_tokens(&catalog, &langs).to_string();
+        for arms in [
+            quote::quote! { Lang::Ar => "العربية", Lang::En => "en", Lang::Ja => "日本語", Lang::ZhTw => "繁體中文", },
+            quote::quote! { Lang::Ar => "ar", Lang::En => "en-US", Lang::Ja => "ja", Lang::ZhTw => "zh-TW", },
+            quote::quote! {
+                Lang::Ar => Direction::Rtl,
+                Lang::En => Direction::Ltr,
+                Lang::Ja => Direction::Ltr,
+                Lang::ZhTw => Direction::Ltr,
+            },
+        ] {
+            assert!(token.contains(&arms.to_string()));
+        }
+    }
+
+    #[test]
+    fn invalid_meta() {
+        let err = CatalogRef::load(&parse_quote! { "files/meta_invalid.yaml" })
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("Metadata of `ja`: `ja_JP` is not a valid BCP 47 tag"));
+    }
+}
@@ -0,0 +1,96 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::catalog::CatalogRef;
+
+/// 実行時にキーと言語から文字列を探す `get` 関数を生成する
+///
+/// キーでソートした表を二分探索し、言語は `Lang` の要素の順番 (`langs` の順番) で並べる
+pub fn lookup_tokens(catalog: &CatalogRef, langs: &[String]) -> TokenStream {
+    let lang_count = langs.len();
+
+    let mut keys: Vec<_> = catalog
+        .yaml
+        .iter()
+        // 複数形のキーは数が必要なので含めない
+        .filter(|(_, localized_text)| localized_text.forms.is_empty())
+        .collect();
+    keys.sort_by_key(|(key, _)| *key);
+
+    let rows = keys.iter().map(|(key, localized_text)| {
+        // lang_t! と同じように、その言語、フォールバック先、all の順で探す
+        let values = langs.iter().map(|lang| {
+            match catalog.chain(lang).find_map(|l| localized_text.get(l)) {
+                Some(text) => quote! { ::core::option::Option::Some(#text) },
+                None => quote! { ::core::option::Option::None },
+            }
+        });
+
+        quote! { (#key, [#(#values),*]), }
+    });
+
+    quote! {
+        /// Returns the text of the yaml `key` in `lang` at runtime.
+        ///
+        /// Falls back in the same way as `lang_t!`, and returns `None` for unknown keys and keys with plural forms.
+        pub fn get(key: &str, lang: Lang) -> ::core::option::Option<&'static str> {
+            const TABLE: &[(&str, [::core::option::Option<&str>; #lang_count])] = &[
+                #(#rows)*
+            ];
+
+            match TABLE.binary_search_by(|(k, _)| ::core::cmp::Ord::cmp(*k, key)) {
+                ::core::result::Result::Ok(i) => TABLE[i].1[lang as usize],
+                ::core::result::Result::Err(_) => ::core::option::Option::None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn lookup_table() {
+        let catalog = CatalogRef::load(&parse_quote! { "files/fallback.yaml" }).unwrap();
+        let langs = ["en", "ja", "pt", "pt_br"].map(String::from);
+
+        let token1 = lookup_tokens(&catalog, &langs).to_string();
+        let token2 = quote! {
+            /// Returns the text of the yaml `key` in `lang` at runtime.
+            ///
+            /// Falls back in the same way as `lang_t!`, and returns `None` for unknown keys and keys with plural forms.
+            pub fn get(key: &str, lang: Lang) -> ::core::option::Option<&'static str> {
+                const TABLE: &[(&str, [::core::option::Option<&str>; 4usize])] = &[
+                    ("bye", [::core::option::Option::Some("Bye"), ::core::option::Option::Some("さようなら"), ::core::option::Option::Some("Bye"), ::core::option::Option::Some("Bye")]),
+                    ("hello", [::core::option::Option::Some("Hello"), ::core::option::Option::Some("こんにちは"), ::core::option::Option::Some("Olá"), ::core::option::Option::Some("Olá")]),
+                    ("title", [::core::option::Option::Some("TITLE"), ::core::option::Option::Some("タイトル"), ::core::option::Option::Some("TITLE"), ::core::option::Option::Some("TITLE")]),
+                ];
+
+                match TABLE.binary_search_by(|(k, _)| ::core::cmp::Ord::cmp(*k, key)) {
+                    ::core::result::Result::Ok(i) => TABLE[i].1[lang as usize],
+                    ::core::result::Result::Err(_) => ::core::option::Option::None,
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2);
+    }
+
+    #[test]
+    fn skip_plural() {
+        let catalog = CatalogRef::load(&parse_quote! { "files/test_file.yaml" }).unwrap();
+        let langs = ["en", "ja", "test1", "zh"].map(String::from);
+
+        let token = lookup_tokens(&catalog, &langs).to_string();
+        assert!(token.contains("\"example1\""));
+        assert!(!token.contains("\"plural1\""));
+        assert!(token
+            .contains(&quote! { ("example5", [::core::option::Option::None, ::core::option::Option::Some("おはよう"), ::core::option::Option::None, ::core::option::Option::None]) }.to_string()));
+    }
+}
@@ -0,0 +1,257 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// BCP 47 や POSIX のロケールから一番近い言語を選ぶ `from_locale`, `negotiate`, `from_env` (std のみ) を生成する
+///
+/// 言語の候補は `bcp47()` のタグを使う
+pub fn negotiate_tokens() -> TokenStream {
+    // 環境変数は std の時だけ読み込める
+    #[cfg(feature = "std")]
+    let from_env = quote! {
+        impl Lang {
+            /// Returns the language of the POSIX locale environment variables.
+            ///
+            /// The locale is the first non-empty one of `LC_ALL`, `LC_MESSAGES` and `LANG`.
+            /// As in GNU gettext, the colon-separated priority list in `LANGUAGE` is tried first unless the locale is `C` or `POSIX`.
+            pub fn from_env() -> ::core::option::Option<Lang> {
+                let var = |name: &str| ::std::env::var(name).ok().filter(|value| !value.is_empty());
+
+                let locale = var("LC_ALL")
+                    .or_else(|| var("LC_MESSAGES"))
+                    .or_else(|| var("LANG"))?;
+
+                // ロケールが C の時は LANGUAGE を使わない
+                LocaleTag::parse(&locale)?;
+
+                if let ::core::option::Option::Some(language) = var("LANGUAGE") {
+                    for locale in language.split(':') {
+                        if let ::core::option::Option::Some(lang) = Lang::from_locale(locale) {
+                            return ::core::option::Option::Some(lang);
+                        }
+                    }
+                }
+
+                Lang::from_locale(&locale)
+            }
+        }
+    };
+    #[cfg(not(feature = "std"))]
+    let from_env = TokenStream::new();
+
+    quote! {
+        impl Lang {
+            /// Returns the language that best matches a BCP 47 tag (`zh-Hant-TW`) or a POSIX locale (`en_US.UTF-8`).
+            ///
+            /// The language subtag must match, and so must the script when both have one (given, or inferred for Chinese).
+            /// The script and region are then used to pick the closest one.
+            pub fn from_locale(locale: &str) -> ::core::option::Option<Lang> {
+                let requested = LocaleTag::parse(locale)?;
+
+                let mut best: ::core::option::Option<(i32, Lang)> = ::core::option::Option::None;
+                for lang in Lang::ALL {
+                    let ::core::option::Option::Some(score) = LocaleTag::parse(lang.bcp47()).and_then(|tag| requested.score(&tag)) else {
+                        continue;
+                    };
+
+                    // 同じ点数なら先の要素を使う
+                    if best.map_or(true, |(best_score, _)| score > best_score) {
+                        best = ::core::option::Option::Some((score, lang));
+                    }
+                }
+
+                best.map(|(_, lang)| lang)
+            }
+
+            /// Returns the best language for the first locale in `locales` (in order of preference) that matches any language.
+            pub fn negotiate(locales: &[&str]) -> ::core::option::Option<Lang> {
+                for locale in locales {
+                    if let ::core::option::Option::Some(lang) = Lang::from_locale(locale) {
+                        return ::core::option::Option::Some(lang);
+                    }
+                }
+
+                ::core::option::Option::None
+            }
+        }
+
+        #from_env
+
+        // ロケールの言語、文字体系、地域のサブタグ
+        #[derive(::core::fmt::Debug, ::core::clone::Clone, ::core::marker::Copy)]
+        struct LocaleTag<'a> {
+            language: &'a str,
+            script: ::core::option::Option<&'a str>,
+            region: ::core::option::Option<&'a str>,
+        }
+
+        impl<'a> LocaleTag<'a> {
+            fn parse(locale: &'a str) -> ::core::option::Option<Self> {
+                use ::core::iter::Iterator as _;
+
+                // en_US.UTF-8@euro の .UTF-8 と @euro を除く
+                let locale = locale.split(['.', '@']).next().unwrap_or_default().trim();
+                if locale.eq_ignore_ascii_case("C") || locale.eq_ignore_ascii_case("POSIX") {
+                    return ::core::option::Option::None;
+                }
+
+                let mut subtags = locale.split(['-', '_']);
+                let language = subtags.next()?;
+                if !(2..=8).contains(&language.len()) || !language.bytes().all(|b| b.is_ascii_alphabetic()) {
+                    return ::core::option::Option::None;
+                }
+
+                let mut script = ::core::option::Option::None;
+                let mut region = ::core::option::Option::None;
+                for subtag in subtags {
+                    let is_alpha = subtag.bytes().all(|b| b.is_ascii_alphabetic());
+                    let is_digit = subtag.bytes().all(|b| b.is_ascii_digit());
+
+                    match subtag.len() {
+                        // 拡張やプライベートの部分は使わない
+                        1 => break,
+                        4 if is_alpha && script.is_none() && region.is_none() => script = ::core::option::Option::Some(subtag),
+                        2 if is_alpha && region.is_none() => region = ::core::option::Option::Some(subtag),
+                        3 if is_digit && region.is_none() => region = ::core::option::Option::Some(subtag),
+                        _ => (),
+                    }
+                }
+
+                // 中国語は地域から文字体系を決める (CLDR の likely subtags と同じく、地域が無ければ簡体字)
+                if script.is_none() && language.eq_ignore_ascii_case("zh") {
+                    script = match region {
+                        ::core::option::Option::Some(region) if ["TW", "HK", "MO"].iter().any(|r| r.eq_ignore_ascii_case(region)) => ::core::option::Option::Some("Hant"),
+                        _ => ::core::option::Option::Some("Hans"),
+                    };
+                }
+
+                ::core::option::Option::Some(Self { language, script, region })
+            }
+
+            // 要求されたロケール (self) と候補の近さ、言語か文字体系が違えば None
+            fn score(&self, candidate: &LocaleTag) -> ::core::option::Option<i32> {
+                if !self.language.eq_ignore_ascii_case(candidate.language) {
+                    return ::core::option::Option::None;
+                }
+
+                // 簡体字と繁体字のように文字体系が違うと読めないので選ばない
+                if let (::core::option::Option::Some(requested), ::core::option::Option::Some(script)) = (self.script, candidate.script) {
+                    if !requested.eq_ignore_ascii_case(script) {
+                        return ::core::option::Option::None;
+                    }
+                }
+
+                let subtag_score = |requested: ::core::option::Option<&str>, candidate: ::core::option::Option<&str>, matched: i32| {
+                    match (requested, candidate) {
+                        (::core::option::Option::Some(requested), ::core::option::Option::Some(candidate)) if requested.eq_ignore_ascii_case(candidate) => matched,
+                        (::core::option::Option::Some(_), ::core::option::Option::Some(_)) => -1,
+                        (::core::option::Option::None, ::core::option::Option::Some(_)) => -1,
+                        (_, ::core::option::Option::None) => 0,
+                    }
+                };
+
+                ::core::option::Option::Some(
+                    subtag_score(self.script, candidate.script, 4)
+                        + subtag_score(self.region, candidate.region, 2),
+                )
+            }
+        }
+    }
+}
+
+/// HTTP の Accept-Language ヘッダーから言語を選ぶ `from_accept_language` を生成する
+///
+/// `*` にはデフォルトの言語、無ければ最初の言語を使う
+#[cfg(feature = "accept-language")]
+pub fn accept_language_tokens(default: Option<&str>) -> TokenStream {
+    let wildcard = match default {
+        Some(default) => {
+            let ident = syn::Ident::new(
+                &super::check_yaml::to_enumval_format(default),
+                proc_macro2::Span::call_site(),
+            );
+            quote! { Lang::#ident }
+        }
+        None => quote! { Lang::ALL[0] },
+    };
+
+    quote! {
+        impl Lang {
+            /// Returns the best language for an HTTP `Accept-Language` header (e.g. `fr-CH, fr;q=0.9, en;q=0.8, *;q=0.5`).
+            ///
+            /// Ranges are tried in order of their q-values, and `*` matches a language that no other range names,
+            /// preferring the default language.
+            pub fn from_accept_language(header: &str) -> ::core::option::Option<Lang> {
+                use ::core::iter::Iterator as _;
+
+                // tag;q=0.8 を (tag, q) にする、q が正しくなければ None
+                fn parse_range(range: &str) -> ::core::option::Option<(&str, f32)> {
+                    let mut parts = range.split(';');
+                    let tag = ::core::iter::Iterator::next(&mut parts)?.trim();
+                    if tag.is_empty() {
+                        return ::core::option::Option::None;
+                    }
+
+                    let mut quality = 1.0;
+                    for param in parts {
+                        let param = param.trim();
+                        if let ::core::option::Option::Some(q) = param.strip_prefix("q=").or_else(|| param.strip_prefix("Q=")) {
+                            quality = q.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                        }
+                    }
+
+                    ::core::option::Option::Some((tag, quality))
+                }
+
+                let ranges = || header.split(',').filter_map(parse_range);
+
+                // * 以外の範囲で指定された (q=0 で除外された場合も含む) 言語か
+                let is_listed = |lang: Lang| {
+                    ranges().any(|(tag, _)| tag != "*" && Lang::from_locale(tag) == ::core::option::Option::Some(lang))
+                };
+
+                let mut best: ::core::option::Option<(f32, Lang)> = ::core::option::Option::None;
+                for (tag, quality) in ranges() {
+                    if quality == 0.0 {
+                        continue;
+                    }
+
+                    let lang = match tag {
+                        // * は他の範囲で指定されていない言語で、デフォルトの言語を優先する
+                        "*" => ::core::iter::once(#wildcard)
+                            .chain(Lang::ALL.iter().copied())
+                            .find(|lang| !is_listed(*lang)),
+                        tag => Lang::from_locale(tag),
+                    };
+
+                    // 同じ q なら先に書かれたものを使う
+                    if let ::core::option::Option::Some(lang) = lang {
+                        if best.map_or(true, |(best_quality, _)| quality > best_quality) {
+                            best = ::core::option::Option::Some((quality, lang));
+                        }
+                    }
+                }
+
+                best.map(|(_, lang)| lang)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens() {
+        let token = negotiate_tokens().to_string();
+
+        assert!(token.contains(
+            "pub fn from_locale (locale : & str) -> :: core :: option :: Option < Lang >"
+        ));
+        assert!(token.contains(
+            "pub fn negotiate (locales : & [& str]) -> :: core :: option :: Option < Lang >"
+        ));
+        #[cfg(feature = "std")]
+        assert!(token.contains("pub fn from_env () -> :: core :: option :: Option < Lang >"));
+    }
+}
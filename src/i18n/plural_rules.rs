@@ -0,0 +1,282 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use super::check_yaml;
+
+/// CLDR の複数形カテゴリ
+pub const CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// 複数形カテゴリの enum と、言語ごとの CLDR の複数形ルールを生成する
+pub fn plural_tokens(langs: &[String]) -> TokenStream {
+    let match_arms = langs.iter().map(|lang| {
+        let ident = Ident::new(&check_yaml::to_enumval_format(lang), Span::call_site());
+        let rule = rule(lang);
+
+        quote! { Lang::#ident => #rule, }
+    });
+
+    let operand_impls = [
+        quote! { u8 },
+        quote! { u16 },
+        quote! { u32 },
+        quote! { u64 },
+        quote! { usize },
+    ]
+    .into_iter()
+    .map(|ty| {
+        quote! {
+            impl PluralOperand for #ty {
+                fn plural_operand(self) -> u64 {
+                    self as u64
+                }
+            }
+        }
+    });
+
+    let signed_operand_impls = [
+        quote! { i8 },
+        quote! { i16 },
+        quote! { i32 },
+        quote! { i64 },
+        quote! { isize },
+    ]
+    .into_iter()
+    .map(|ty| {
+        quote! {
+            impl PluralOperand for #ty {
+                fn plural_operand(self) -> u64 {
+                    self.unsigned_abs() as u64
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// CLDR plural category used by `langrustang::plural_t!`
+        #[derive(
+            ::core::fmt::Debug,
+            ::core::clone::Clone,
+            ::core::marker::Copy,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::hash::Hash,
+        )]
+        pub enum PluralCategory {
+            Zero,
+            One,
+            Two,
+            Few,
+            Many,
+            Other,
+        }
+
+        /// Integer types that can be passed as the count of `langrustang::plural_t!`
+        pub trait PluralOperand: ::core::marker::Copy {
+            fn plural_operand(self) -> u64;
+        }
+
+        #(#operand_impls)*
+        #(#signed_operand_impls)*
+
+        impl Lang {
+            /// Returns the CLDR plural category of `n` in this language.
+            #[allow(unused_variables)]
+            pub fn plural_category<N: PluralOperand>(self, n: N) -> PluralCategory {
+                let n = n.plural_operand();
+
+                match self {
+                    #(#match_arms)*
+                }
+            }
+        }
+    }
+}
+
+/// 言語キーに対応する複数形ルールの式を返す (整数のみ対応)
+///
+/// 知らない言語は英語と同じく 1 を `one`、それ以外を `other` とする
+fn rule(lang: &str) -> TokenStream {
+    let base = lang.split('_').next().unwrap_or(lang);
+
+    match (base, lang) {
+        // 複数形の区別がない言語
+        (
+            "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "km" | "yue" | "jv"
+            | "bo" | "dz" | "ig" | "yo" | "sah",
+            _,
+        ) => quote! { PluralCategory::Other },
+
+        // 0 と 1 が one になる言語
+        ("hi" | "bn" | "fa" | "gu" | "kn" | "zu" | "am" | "as" | "ak" | "ln" | "ti", _) => quote! {
+            match n {
+                0 | 1 => PluralCategory::One,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        // ポルトガル語 (ポルトガル) は 1 のみ one
+        ("pt", "pt_pt") => quote! {
+            match n {
+                1 => PluralCategory::One,
+                n if n != 0 && n % 1_000_000 == 0 => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("fr" | "pt", _) => quote! {
+            match n {
+                0 | 1 => PluralCategory::One,
+                n if n % 1_000_000 == 0 => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("es" | "it" | "ca", _) => quote! {
+            match n {
+                1 => PluralCategory::One,
+                n if n != 0 && n % 1_000_000 == 0 => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("ru" | "uk" | "be", _) => quote! {
+            match (n % 10, n % 100) {
+                (1, m) if m != 11 => PluralCategory::One,
+                (2..=4, m) if !(12..=14).contains(&m) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            }
+        },
+
+        ("pl", _) => quote! {
+            match (n, n % 10, n % 100) {
+                (1, _, _) => PluralCategory::One,
+                (_, 2..=4, m) if !(12..=14).contains(&m) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            }
+        },
+
+        ("hr" | "sr" | "bs" | "sh", _) => quote! {
+            match (n % 10, n % 100) {
+                (1, m) if m != 11 => PluralCategory::One,
+                (2..=4, m) if !(12..=14).contains(&m) => PluralCategory::Few,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("cs" | "sk", _) => quote! {
+            match n {
+                1 => PluralCategory::One,
+                2..=4 => PluralCategory::Few,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("lt", _) => quote! {
+            match (n % 10, n % 100) {
+                (_, 11..=19) => PluralCategory::Other,
+                (1, _) => PluralCategory::One,
+                (2..=9, _) => PluralCategory::Few,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("lv", _) => quote! {
+            match (n % 10, n % 100) {
+                (0, _) | (_, 11..=19) => PluralCategory::Zero,
+                (1, _) => PluralCategory::One,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("ro" | "mo", _) => quote! {
+            match (n, n % 100) {
+                (1, _) => PluralCategory::One,
+                (0, _) | (_, 1..=19) => PluralCategory::Few,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("sl", _) => quote! {
+            match n % 100 {
+                1 => PluralCategory::One,
+                2 => PluralCategory::Two,
+                3 | 4 => PluralCategory::Few,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("he" | "iw", _) => quote! {
+            match n {
+                1 => PluralCategory::One,
+                2 => PluralCategory::Two,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("ar" | "ars", _) => quote! {
+            match (n, n % 100) {
+                (0, _) => PluralCategory::Zero,
+                (1, _) => PluralCategory::One,
+                (2, _) => PluralCategory::Two,
+                (_, 3..=10) => PluralCategory::Few,
+                (_, 11..=99) => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("ga", _) => quote! {
+            match n {
+                1 => PluralCategory::One,
+                2 => PluralCategory::Two,
+                3..=6 => PluralCategory::Few,
+                7..=10 => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("cy", _) => quote! {
+            match n {
+                0 => PluralCategory::Zero,
+                1 => PluralCategory::One,
+                2 => PluralCategory::Two,
+                3 => PluralCategory::Few,
+                6 => PluralCategory::Many,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        ("is" | "mk", _) => quote! {
+            match (n % 10, n % 100) {
+                (1, m) if m != 11 => PluralCategory::One,
+                _ => PluralCategory::Other,
+            }
+        },
+
+        // en, de, nl, sv など 1 のみ one になる言語
+        _ => quote! {
+            match n {
+                1 => PluralCategory::One,
+                _ => PluralCategory::Other,
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn rule_by_base_lang() {
+        assert_eq!(rule("ja").to_string(), rule("ja_jp").to_string());
+        assert_eq!(
+            rule("zh_tw").to_string(),
+            quote! { PluralCategory::Other }.to_string()
+        );
+        assert_eq!(rule("en").to_string(), rule("some_key_2").to_string());
+        assert_ne!(rule("pt").to_string(), rule("pt_pt").to_string());
+    }
+}
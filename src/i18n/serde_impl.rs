@@ -0,0 +1,54 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// `serde = true` の時に、yaml の言語キーを使う Serialize と Deserialize を生成する
+///
+/// Deserialize は yaml の言語キーと、FromStr が受け付ける文字列を受け付ける
+pub fn serde_tokens(langs: &[String]) -> TokenStream {
+    quote! {
+        impl ::serde::Serialize for Lang {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for Lang {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
+                struct LangVisitor;
+
+                impl ::serde::de::Visitor<'_> for LangVisitor {
+                    type Value = Lang;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str("a language key")
+                    }
+
+                    fn visit_str<E: ::serde::de::Error>(self, value: &str) -> ::core::result::Result<Lang, E> {
+                        for lang in Lang::ALL {
+                            if lang.as_str() == value {
+                                return ::core::result::Result::Ok(lang);
+                            }
+                        }
+
+                        value
+                            .parse()
+                            .map_err(|_| E::unknown_variant(value, &[#(#langs),*]))
+                    }
+                }
+
+                deserializer.deserialize_str(LangVisitor)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants() {
+        let token = serde_tokens(&["en".into(), "zh_tw".into()]).to_string();
+        assert!(token.contains(&quote! { E::unknown_variant(value, &["en", "zh_tw"]) }.to_string()));
+    }
+}
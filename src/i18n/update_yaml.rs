@@ -1,38 +1,40 @@
-use crate::{i18n::LangYaml, YAML_DATA, YAML_MODIFIED_TIME, YAML_PATH};
-
-/// 更新日時をチェックして更新されていたら static 変数を更新する
-pub fn if_update_reload_yaml() -> anyhow::Result<()> {
-    let path = {
-        let lock = YAML_PATH.lock().unwrap();
-        lock.clone()
-    };
-    let metadata = std::fs::metadata(&path)?;
-    let modified_time = metadata.modified()?;
-
-    let cache_modified_time = {
-        let lock = YAML_MODIFIED_TIME.read().unwrap();
-        lock.clone()
-    };
-
-    if modified_time != cache_modified_time {
-        // yaml を読み込み
-        let yaml_string = std::fs::read_to_string(&path)?;
-
-        // yaml への変換と static への保存
-        {
-            let mut lock = YAML_DATA.lock().unwrap();
-
-            if let Some(_) = *lock {
-                let yaml: LangYaml = serde_yaml::from_str(&yaml_string)?;
-                *lock = Some(yaml);
-            }
-        };
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
+
+use crate::{
+    catalog::Catalog,
+    i18n::yaml_files::{self, YamlFile},
+};
+
+/// 読み込んだファイルの組ごとの、更新日時と読み込んだ結果
+type CatalogCache = HashMap<Vec<YamlFile>, (SystemTime, Arc<Catalog>)>;
+
+/// yaml の読み込み結果のキャッシュ (マクロの呼び出しごとに読み込み直さないため)
+static CATALOG_CACHE: LazyLock<Mutex<CatalogCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 更新日時をチェックして、更新されていなければキャッシュを、更新されていたら読み込み直したものを返す
+pub fn load_cached(files: &[YamlFile]) -> Result<Arc<Catalog>, String> {
+    let modified_time = yaml_files::modified_time(files).map_err(|err| err.to_string())?;
 
-        {
-            let mut lock = YAML_MODIFIED_TIME.write().unwrap();
-            *lock = modified_time;
+    {
+        let lock = CATALOG_CACHE.lock().unwrap();
+        if let Some((cache_modified_time, catalog)) = lock.get(files) {
+            if *cache_modified_time == modified_time {
+                return Ok(catalog.clone());
+            }
         }
     }
 
-    Ok(())
+    // yaml を読み込み
+    let catalog = Arc::new(Catalog::from_yaml(yaml_files::load(files)?)?);
+
+    {
+        let mut lock = CATALOG_CACHE.lock().unwrap();
+        lock.insert(files.to_vec(), (modified_time, catalog.clone()));
+    }
+
+    Ok(catalog)
 }
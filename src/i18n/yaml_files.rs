@@ -0,0 +1,316 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::lang_yaml::{LangYaml, LocalizedText};
+
+/// 読み込む yaml ファイル
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum YamlFile {
+    /// 全ての言語を含む yaml
+    Catalog(PathBuf),
+    /// ファイル名が言語キーになる yaml
+    Locale(PathBuf, String),
+}
+
+impl YamlFile {
+    pub fn path(&self) -> &Path {
+        match self {
+            YamlFile::Catalog(path) => path,
+            YamlFile::Locale(path, _) => path,
+        }
+    }
+}
+
+/// 相対パスを、マクロを呼び出したクレートの `CARGO_MANIFEST_DIR` からのパスにする
+///
+/// 絶対パスはそのまま使う
+pub fn resolve(spec: &str) -> PathBuf {
+    let pathbuf = PathBuf::from(spec);
+
+    match (
+        pathbuf.is_absolute(),
+        std::env::var_os("CARGO_MANIFEST_DIR"),
+    ) {
+        (false, Some(manifest_dir)) => Path::new(&manifest_dir).join(pathbuf),
+        _ => pathbuf,
+    }
+}
+
+/// i18n! に渡されたパスから読み込む yaml ファイルを集める
+///
+/// ディレクトリなら中の `<言語キー>.yaml`、ファイル名に `*` か `?` があればそれに一致するファイル、
+/// それ以外は1つの yaml ファイルとして扱う
+pub fn collect(spec: &str) -> Result<Vec<YamlFile>, String> {
+    let pathbuf = resolve(spec);
+    let display_path = pathbuf.canonicalize().unwrap_or_else(|_| pathbuf.clone());
+
+    // ディレクトリ
+    if pathbuf.is_dir() {
+        let mut files = vec![];
+        for path in read_yaml_dir(&pathbuf)? {
+            let Some(lang) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            files.push(YamlFile::Locale(path.clone(), lang.to_string()));
+        }
+
+        if files.is_empty() {
+            return Err(format!("No yaml files in directory: {:?}", display_path));
+        }
+        return Ok(files);
+    }
+
+    if spec.ends_with('/') || spec.ends_with('\\') {
+        return Err(format!("Directory is not found: {:?}", display_path));
+    }
+
+    // glob
+    let file_name = pathbuf
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+
+    if file_name.contains(['*', '?']) {
+        let dir = match pathbuf.parent() {
+            Some(parent) if parent != Path::new("") => parent.to_path_buf(),
+            _ => resolve("."),
+        };
+
+        let files: Vec<_> = read_yaml_dir(&dir)?
+            .into_iter()
+            .filter(|path| {
+                let name = path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default();
+                wildcard_match(file_name, name)
+            })
+            .map(YamlFile::Catalog)
+            .collect();
+
+        if files.is_empty() {
+            return Err(format!("No yaml files match: {}", spec));
+        }
+        return Ok(files);
+    }
+
+    // yaml かどうか確認
+    if !spec.ends_with(".yaml") {
+        return Err("expected .yaml file path".into());
+    }
+
+    // ファイルが存在するか確認
+    if !pathbuf.exists() {
+        return Err(format!("File is not found: {:?}", display_path));
+    }
+
+    Ok(vec![YamlFile::Catalog(pathbuf)])
+}
+
+/// ディレクトリ内の .yaml ファイルをソートして返す
+fn read_yaml_dir(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|_| format!("Failed to open directory: {:?}", dir))?;
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "yaml"))
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// `*` と `?` だけのワイルドカードの一致
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // dp[j]: pattern[..i] が text[..j] に一致するか
+    let mut dp = vec![false; text.len() + 1];
+    dp[0] = true;
+
+    for p in pattern {
+        let mut next = vec![false; text.len() + 1];
+        for j in 0..=text.len() {
+            next[j] = match p {
+                '*' => dp[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && dp[j - 1],
+                c => j > 0 && dp[j - 1] && text[j - 1] == c,
+            };
+        }
+        dp = next;
+    }
+
+    dp[text.len()]
+}
+
+/// yaml ファイルを読み込んで1つにまとめる
+///
+/// 同じキーの同じ言語が複数のファイルにある場合はエラーにする
+pub fn load(files: &[YamlFile]) -> Result<LangYaml, String> {
+    let mut merged: HashMap<String, LocalizedText> = HashMap::new();
+    let mut origins: HashMap<(String, String), &Path> = HashMap::new();
+    let mut fallback = BTreeMap::new();
+    let mut fallback_origins: HashMap<String, &Path> = HashMap::new();
+    let mut default: Option<(String, &Path)> = None;
+    let mut meta = BTreeMap::new();
+    let mut meta_origins: HashMap<String, &Path> = HashMap::new();
+
+    for file in files {
+        let path = file.path();
+        let display_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        let yaml_string = std::fs::read_to_string(path)
+            .map_err(|_| format!("Failed to open file: {:?}", display_path))?;
+
+        let yaml = match file {
+            YamlFile::Catalog(_) => serde_yaml::from_str(&yaml_string).map_err(|e| e.to_string()),
+            YamlFile::Locale(_, lang) => LangYaml::from_locale_str(lang, &yaml_string),
+        }
+        .map_err(|err| format!("Failed to parse yaml {:?}: {}", display_path, err))?;
+
+        if let Some(lang) = yaml.default {
+            if let Some((_, origin)) = default.replace((lang, path)) {
+                return Err(format!(
+                    "The default language is defined in both {:?} and {:?}",
+                    origin, path
+                ));
+            }
+        }
+
+        for (lang, targets) in yaml.fallback {
+            if let Some(origin) = fallback_origins.insert(lang.clone(), path) {
+                return Err(format!(
+                    "Fallback of `{}` is defined in both {:?} and {:?}",
+                    lang, origin, path
+                ));
+            }
+            fallback.insert(lang, targets);
+        }
+
+        for (lang, lang_meta) in yaml.meta {
+            if let Some(origin) = meta_origins.insert(lang.clone(), path) {
+                return Err(format!(
+                    "Metadata of `{}` is defined in both {:?} and {:?}",
+                    lang, origin, path
+                ));
+            }
+            meta.insert(lang, lang_meta);
+        }
+
+        for (key, localized_text) in yaml.texts {
+            // 他のファイルで定義されていないか確認
+            for lang in localized_text.langs() {
+                if let Some(origin) = origins.insert((key.clone(), lang.clone()), path) {
+                    return Err(format!(
+                        "Key: {}, language `{}` is defined in both {:?} and {:?}",
+                        key, lang, origin, path
+                    ));
+                }
+            }
+
+            let entry = merged.entry(key).or_default();
+            entry.elem.extend(localized_text.elem);
+            entry.forms.extend(localized_text.forms);
+        }
+    }
+
+    Ok(LangYaml {
+        texts: merged,
+        fallback,
+        default: default.map(|(lang, _)| lang),
+        meta,
+    })
+}
+
+/// 全てのファイルの中で最も新しい更新日時
+pub fn modified_time(files: &[YamlFile]) -> std::io::Result<SystemTime> {
+    let mut latest = SystemTime::UNIX_EPOCH;
+    for file in files {
+        latest = latest.max(std::fs::metadata(file.path())?.modified()?);
+    }
+
+    Ok(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn manifest_path(path: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join(path)
+    }
+
+    #[test]
+    fn resolve_path() {
+        assert_eq!(
+            resolve("files/test_file.yaml"),
+            manifest_path("files/test_file.yaml")
+        );
+        assert_eq!(resolve("/abs/lang.yaml"), PathBuf::from("/abs/lang.yaml"));
+    }
+
+    #[test]
+    fn collect_dir() {
+        let files = collect("files/locales").unwrap();
+        assert_eq!(
+            files,
+            [
+                YamlFile::Locale(manifest_path("files/locales/en.yaml"), "en".into()),
+                YamlFile::Locale(manifest_path("files/locales/ja.yaml"), "ja".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn collect_glob() {
+        let files = collect("files/locales/*n.yaml").unwrap();
+        assert_eq!(
+            files,
+            [YamlFile::Catalog(manifest_path("files/locales/en.yaml"))]
+        );
+
+        let err = collect("files/locales/*.yml").unwrap_err();
+        assert!(err.contains("No yaml files match"));
+    }
+
+    #[test]
+    fn load_dir() {
+        let yaml = load(&collect("files/locales").unwrap()).unwrap();
+
+        assert_eq!(yaml["hello"]["en"], "Hello");
+        assert_eq!(yaml["hello"]["ja"], "こんにちは");
+        assert_eq!(yaml["menu.file"]["ja"], "ファイル");
+        assert!(yaml["files"].forms.contains_key("en"));
+
+        // other というキーがある名前空間と、!select タグの select
+        assert_eq!(yaml["errors.network"]["en"], "Network error");
+        assert_eq!(yaml["errors.other"]["ja"], "不明なエラー");
+        assert_eq!(yaml["replied"].forms["en"]["other"], "They replied");
+        assert_eq!(yaml["replied"]["ja"], "返信しました");
+    }
+
+    #[test]
+    fn load_conflict() {
+        let mut files = collect("files/locales").unwrap();
+        files.extend(collect("files/conflict.yaml").unwrap());
+
+        let err = load(&files).unwrap_err();
+        assert!(err.contains("Key: hello, language `ja` is defined in both"));
+        assert!(err.contains("files/locales/ja.yaml") && err.contains("files/conflict.yaml"));
+    }
+
+    #[test]
+    fn wildcard() {
+        assert!(wildcard_match("*.yaml", "en.yaml"));
+        assert!(wildcard_match("??.yaml", "ja.yaml"));
+        assert!(!wildcard_match("??.yaml", "zh_tw.yaml"));
+        assert!(!wildcard_match("*.yaml", "en.yml"));
+    }
+}
@@ -3,12 +3,11 @@ mod create_literal;
 use proc_macro2::TokenStream;
 use syn::{
     parse::{ParseStream, Parser},
-    punctuated::Punctuated,
     spanned::Spanned,
-    Error, Expr, ExprLit, Lit, Result, Token,
+    Error, Result,
 };
 
-use crate::{i18n::update_yaml::if_update_reload_yaml, YAML_DATA, YAML_LANGS};
+use crate::{catalog, select_t};
 
 pub fn _lang_t(tokens: TokenStream) -> TokenStream {
     lang_t_parse
@@ -17,12 +16,11 @@ pub fn _lang_t(tokens: TokenStream) -> TokenStream {
 }
 
 fn lang_t_parse(input: ParseStream) -> Result<TokenStream> {
-    let yaml_langs = {
-        let lock = YAML_LANGS.lock().unwrap();
-        lock.clone()
+    let Some(catalog) = catalog::parse(input)? else {
+        return catalog::redirect("lang_t", input);
     };
 
-    let parsed = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+    let parsed = catalog.parse_args(input)?;
 
     // 簡単にリターンできる用のクロージャ
     let err_return = |s: String| Err(Error::new(parsed.span(), s));
@@ -33,39 +31,16 @@ fn lang_t_parse(input: ParseStream) -> Result<TokenStream> {
         _ => (),
     };
 
-    // yaml が更新されていたら static を更新
-    let _ = if_update_reload_yaml();
-
-    // yamldata の取得 i18n が使われていなかったら返す
-    let yaml_data = {
-        let lock = YAML_DATA.lock().unwrap();
-
-        match lock.as_ref() {
-            Some(value) => value.clone(),
-            None => {
-                return err_return(
-                    "langrustang::i18n is not used, please set the yaml path.".into(),
-                )
-            }
-        }
+    // 指定されたキーの翻訳を取得
+    let (key, localized_text) = match catalog.localized_text(&parsed) {
+        Ok(found) => found,
+        Err(err) => return err_return(err),
     };
 
-    // 指定された文字列リテラルを取得
-    let key = {
-        match parsed.get(0) {
-            Some(Expr::Lit(ExprLit {
-                lit: Lit::Str(lit_str),
-                ..
-            })) => lit_str.value(),
-
-            _ => return err_return("Failed get param".into()),
-        }
-    };
-
-    // 存在しないキーなら返す
-    let Some(localized_text) = yaml_data.get(&key) else {
-        return err_return(format!("Unknown Key: {}", key));
-    };
+    // 複数形や select の場合は plural_t! か select_t! を使う
+    if !localized_text.forms.is_empty() {
+        return err_return(select_t::forms_error(&key, localized_text));
+    }
 
     // 言語キーが all のみかどうか
     let is_allonly_key =
@@ -86,7 +61,7 @@ fn lang_t_parse(input: ParseStream) -> Result<TokenStream> {
             // all キーのみの場合、引数が多すぎるので返す
             match is_allonly_key {
                 true => err_return(format!("Key: {} is Not Localized", key)),
-                false => create_literal::literal_and_lang(parsed, localized_text, yaml_langs),
+                false => create_literal::literal_and_lang(parsed, localized_text, &catalog),
             }
         }
 
@@ -99,50 +74,39 @@ mod tests {
     use pretty_assertions::assert_eq;
     use quote::quote;
 
-    use crate::i18n::_i18n;
-
     use super::*;
 
     #[test]
     #[ignore]
     fn dbg() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token = _lang_t(quote! { "example1", lang }).to_string();
+        let token =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "example1", lang }).to_string();
         dbg!(token);
     }
 
     #[test]
     fn check_1arg_localized() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token = _lang_t(quote! { "example2" }).to_string();
+        let token = _lang_t(quote! { @catalog ("files/test_file.yaml") "example2" }).to_string();
         assert!(token.contains("Key: example2 is Localized"));
     }
 
     #[test]
     fn check_2arg_localized() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token = _lang_t(quote! { "example1", lang }).to_string();
+        let token =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "example1", lang }).to_string();
         assert!(token.contains("Key: example1 is Not Localized"));
     }
 
     #[test]
     fn test_lang_lang() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _lang_t(quote! { "example2", lang }).to_string();
+        let token1 =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "example2", lang }).to_string();
         let token2 = quote! {
-            {
-                use crate::_langrustang_autogen::Lang::*;
-
-                match lang {
-                    En => "hello!",
-                    Ja => "おはよう",
-                    Test1 => "TEST1",
-                    Zh => "你好",
-                }
+            match lang {
+                crate::_langrustang_autogen::Lang::En => "hello!",
+                crate::_langrustang_autogen::Lang::Ja => "おはよう",
+                crate::_langrustang_autogen::Lang::Test1 => "TEST1",
+                crate::_langrustang_autogen::Lang::Zh => "你好",
             }
         }
         .to_string();
@@ -152,34 +116,140 @@ mod tests {
 
     #[test]
     fn test_missing() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _lang_t(quote! { "example5", lang }).to_string();
+        let token1 =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "example5", lang }).to_string();
         assert!(token1.contains("Missing language key"));
     }
 
     #[test]
     fn test_lang_all() {
-        _i18n(quote! {"files/test_file.yaml"});
-
-        let token1 = _lang_t(quote! { "example1" }).to_string();
+        let token1 = _lang_t(quote! { @catalog ("files/test_file.yaml") "example1" }).to_string();
         let token2 = quote! { "ALL_EXAMPLE" }.to_string();
 
         assert_eq!(token1, token2)
     }
 
     #[test]
-    fn expect_str() {
-        _i18n(quote! {"files/test_file.yaml"});
+    fn test_nested_key() {
+        let token1 = _lang_t(quote! { @catalog ("files/test_file.yaml") "nested.greeting", lang })
+            .to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => "Hello",
+                crate::_langrustang_autogen::Lang::Ja => "こんにちは",
+                crate::_langrustang_autogen::Lang::Test1 => "Hi",
+                crate::_langrustang_autogen::Lang::Zh => "Hi",
+            }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
 
-        let token1 = _lang_t(quote! {}).to_string();
+        let token1 =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "nested.deep.title" }).to_string();
+        assert_eq!(token1, quote! { "DEEP" }.to_string());
+    }
+
+    #[test]
+    fn locale_namespace() {
+        let token1 =
+            _lang_t(quote! { @catalog ("files/locales") "errors.network", lang }).to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => "Network error",
+                crate::_langrustang_autogen::Lang::Ja => "ネットワークエラー",
+            }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2);
+    }
+
+    #[test]
+    fn expect_str() {
+        let token1 = _lang_t(quote! { @catalog ("files/test_file.yaml") }).to_string();
         assert!(dbg!(token1).contains("Expected string literal"));
     }
 
     #[test]
-    #[ignore]
-    fn not_used_i18n() {
-        let token1 = _lang_t(quote! { "example1" }).to_string();
-        assert!(dbg!(token1).contains("langrustang::i18n is not used, please set the yaml path."));
+    fn redirect_to_catalog() {
+        let token1 = _lang_t(quote! { "example2", lang }).to_string();
+        let token2 = quote! {
+            crate::_langrustang_autogen::__langrustang_catalog! { lang_t! { "example2", lang } }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2)
+    }
+
+    #[test]
+    fn named_catalog() {
+        let token1 = _lang_t(quote! { ui, "example2", lang }).to_string();
+        let token2 = quote! {
+            crate::ui::__langrustang_catalog! { lang_t! { "example2", lang } }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+
+        let token1 =
+            _lang_t(quote! { @catalog (name = ui, "files/test_file.yaml") "example2", lang })
+                .to_string();
+        assert!(token1.contains(&quote! { crate::ui::Lang::En => "hello!", }.to_string()));
+    }
+
+    #[test]
+    fn current_lang() {
+        let token1 =
+            _lang_t(quote! { @catalog (name = ui, "files/test_file.yaml") "example2", @current })
+                .to_string();
+        assert!(token1.contains(&quote! { match crate::ui::current_lang() }.to_string()));
+
+        let token1 =
+            _lang_t(quote! { @catalog ("files/test_file.yaml") "example2", @ current }).to_string();
+        assert!(token1
+            .contains(&quote! { match crate::_langrustang_autogen::current_lang() }.to_string()));
+    }
+
+    #[test]
+    fn fallback_chain() {
+        let token1 = _lang_t(quote! { @catalog ("files/fallback.yaml") "bye", lang }).to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => "Bye",
+                crate::_langrustang_autogen::Lang::Ja => "さようなら",
+                crate::_langrustang_autogen::Lang::Pt => "Bye",
+                crate::_langrustang_autogen::Lang::PtBr => "Bye",
+            }
+        }
+        .to_string();
+        assert_eq!(token1, token2);
+
+        let token1 = _lang_t(quote! { @catalog ("files/fallback.yaml") "hello", lang }).to_string();
+        assert!(token1
+            .contains(&quote! { crate::_langrustang_autogen::Lang::PtBr => "Olá" }.to_string()));
+
+        // all より先にフォールバック先を使う
+        let token1 = _lang_t(
+            quote! { @catalog ("files/fallback.yaml", fallback = { "en": "ja" }) "title", lang },
+        )
+        .to_string();
+        assert!(token1
+            .contains(&quote! { crate::_langrustang_autogen::Lang::En => "タイトル" }.to_string()));
+        assert!(token1.contains(
+            &quote! { crate::_langrustang_autogen::Lang::PtBr => "タイトル" }.to_string()
+        ));
+    }
+
+    #[test]
+    fn default_lang_fallback() {
+        let token1 = _lang_t(quote! { @catalog ("files/default.yaml") "bye", lang }).to_string();
+        let token2 = quote! {
+            match lang {
+                crate::_langrustang_autogen::Lang::En => "さようなら",
+                crate::_langrustang_autogen::Lang::Ja => "さようなら",
+            }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2);
     }
 }
@@ -1,22 +1,167 @@
-use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, ops::Deref};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_yaml::{Mapping, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Deref,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(transparent)]
-pub(crate) struct LangYaml(pub HashMap<String, LocalizedText>);
+use crate::i18n::plural_rules::CATEGORIES;
+
+/// トップレベルに書く、言語ごとのフォールバック先の指定
+pub const FALLBACK_KEY: &str = "_fallback";
+
+/// トップレベルに書く、デフォルトの言語の指定
+pub const DEFAULT_KEY: &str = "_default";
+
+/// トップレベルに書く、言語ごとの名前や BCP 47 タグなどの情報
+pub const META_KEY: &str = "_meta";
+
+/// select のケースであることを示すタグ (`!select { female: ..., other: ... }`)
+pub const SELECT_TAG: &str = "select";
+
+/// yaml のキーをドット区切りに平坦化したもの
+///
+/// マッピングの値に文字列 (または複数形や select のマッピング) が1つでもあれば翻訳、
+/// それ以外は名前空間として扱い、子のキーを `.` でつなげる
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LangYaml {
+    pub texts: HashMap<String, LocalizedText>,
+    /// `_fallback` で指定された、言語ごとのフォールバック先
+    pub fallback: BTreeMap<String, Vec<String>>,
+    /// `_default` で指定された、デフォルトの言語
+    pub default: Option<String>,
+    /// `_meta` で指定された、言語ごとの情報
+    pub meta: BTreeMap<String, LangMeta>,
+}
+
+/// `_meta` に書く言語の情報
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LangMeta {
+    /// その言語での言語の名前 (`日本語` など)
+    pub name: Option<String>,
+    /// BCP 47 タグ (`ja-JP` など)
+    pub tag: Option<String>,
+    /// 文字の方向 (`ltr` か `rtl`)
+    pub dir: Option<String>,
+}
 
 impl Deref for LangYaml {
     type Target = HashMap<String, LocalizedText>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.texts
+    }
+}
+
+impl<'de> Deserialize<'de> for LangYaml {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut yaml = Self::default();
+
+        match Value::deserialize(deserializer)? {
+            Value::Mapping(mut mapping) => {
+                // _fallback: { pt_br: pt, pt: en }
+                match mapping.remove(FALLBACK_KEY) {
+                    Some(Value::Mapping(fallback)) => {
+                        for (lang, targets) in fallback.iter() {
+                            let Some(lang) = scalar_to_string(lang) else {
+                                return Err(D::Error::custom(format!(
+                                    "Language key in `{}` must be a string",
+                                    FALLBACK_KEY
+                                )));
+                            };
+                            let targets =
+                                fallback_targets(&lang, targets).map_err(D::Error::custom)?;
+                            yaml.fallback.insert(lang, targets);
+                        }
+                    }
+                    Some(_) => {
+                        return Err(D::Error::custom(format!(
+                            "`{}` must be a mapping of language keys",
+                            FALLBACK_KEY
+                        )))
+                    }
+                    None => (),
+                }
+
+                // _default: en
+                if let Some(default) = mapping.remove(DEFAULT_KEY) {
+                    match scalar_to_string(&default) {
+                        Some(default) => yaml.default = Some(default),
+                        None => {
+                            return Err(D::Error::custom(format!(
+                                "`{}` must be a language key",
+                                DEFAULT_KEY
+                            )))
+                        }
+                    }
+                }
+
+                // _meta: { ja: { name: 日本語, tag: ja-JP, dir: ltr } }
+                if let Some(meta) = mapping.remove(META_KEY) {
+                    yaml.meta = serde_yaml::from_value(meta).map_err(|err| {
+                        D::Error::custom(format!("Failed to parse `{}`: {}", META_KEY, err))
+                    })?;
+                }
+
+                flatten("", &mapping, &mut yaml.texts).map_err(D::Error::custom)?;
+                check_select_without_other(&yaml).map_err(D::Error::custom)?
+            }
+            Value::Null => (),
+            _ => return Err(D::Error::custom("The top level of yaml must be a mapping")),
+        }
+
+        Ok(yaml)
+    }
+}
+
+impl LangYaml {
+    /// ファイル名が言語キーになる、1つの言語だけの yaml を読み込む
+    ///
+    /// 文字列、キーが全て複数形カテゴリのマッピング、`!select` タグを付けたマッピングが翻訳、
+    /// それ以外のマッピングは名前空間になる (名前空間に `other` というキーがあっても select にならない)
+    ///
+    /// トップレベルの `_fallback` は、この言語のフォールバック先、`_meta` はこの言語の情報になる
+    pub fn from_locale_str(lang: &str, s: &str) -> Result<Self, String> {
+        let mut yaml = Self::default();
+
+        match serde_yaml::from_str(s).map_err(|err| err.to_string())? {
+            Value::Mapping(mut mapping) => {
+                // _fallback: pt
+                if let Some(targets) = mapping.remove(FALLBACK_KEY) {
+                    yaml.fallback
+                        .insert(lang.to_string(), fallback_targets(lang, &targets)?);
+                }
+
+                // _meta: { name: 日本語, tag: ja-JP, dir: ltr }
+                if let Some(meta) = mapping.remove(META_KEY) {
+                    let meta = serde_yaml::from_value(meta)
+                        .map_err(|err| format!("Failed to parse `{}`: {}", META_KEY, err))?;
+                    yaml.meta.insert(lang.to_string(), meta);
+                }
+
+                flatten_locale(lang, "", &mapping, &mut yaml.texts)?
+            }
+            Value::Null => (),
+            _ => return Err("The top level of yaml must be a mapping".into()),
+        }
+
+        Ok(yaml)
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct LocalizedText {
-    #[serde(flatten)]
     pub elem: HashMap<String, String>,
+    /// 複数形などの形ごとに分かれた文字列
+    pub forms: HashMap<String, BTreeMap<String, String>>,
+}
+
+impl LocalizedText {
+    /// 文字列と形ごとの文字列の両方を含めた言語キー
+    pub fn langs(&self) -> impl Iterator<Item = &String> {
+        self.elem.keys().chain(self.forms.keys())
+    }
 }
 
 impl Deref for LocalizedText {
@@ -26,3 +171,513 @@ impl Deref for LocalizedText {
         &self.elem
     }
 }
+
+/// yaml の言語キーの値
+#[derive(Debug, Clone)]
+pub(crate) enum LangValue {
+    Text(String),
+    Forms(BTreeMap<String, String>),
+}
+
+impl From<HashMap<String, LangValue>> for LocalizedText {
+    fn from(value: HashMap<String, LangValue>) -> Self {
+        let mut elem = HashMap::new();
+        let mut forms = HashMap::new();
+
+        for (lang, value) in value {
+            match value {
+                LangValue::Text(text) => {
+                    elem.insert(lang, text);
+                }
+                LangValue::Forms(map) => {
+                    forms.insert(lang, map);
+                }
+            }
+        }
+
+        Self { elem, forms }
+    }
+}
+
+/// 文字列、数値、真偽値を文字列にする
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// フォールバック先の言語キー、1つの言語キーか言語キーのリスト
+fn fallback_targets(lang: &str, value: &Value) -> Result<Vec<String>, String> {
+    let err = || {
+        format!(
+            "Fallback of `{}` must be a language key or a list of language keys",
+            lang
+        )
+    };
+
+    match value {
+        Value::Sequence(seq) => seq
+            .iter()
+            .map(|v| scalar_to_string(v).ok_or_else(err))
+            .collect(),
+        value => scalar_to_string(value).map(|s| vec![s]).ok_or_else(err),
+    }
+}
+
+/// `!select` タグを付けたマッピングなら、その中身を返す
+fn select_cases(value: &Value) -> Option<&Mapping> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag == SELECT_TAG => match &tagged.value {
+            Value::Mapping(mapping) => Some(mapping),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// キーが全て CLDR の複数形カテゴリの、文字列だけのマッピングかどうか
+fn is_plural_forms(mapping: &Mapping) -> bool {
+    !mapping.is_empty()
+        && mapping.values().all(|v| scalar_to_string(v).is_some())
+        && mapping
+            .keys()
+            .all(|key| scalar_to_string(key).is_some_and(|key| CATEGORIES.contains(&key.as_str())))
+}
+
+/// 複数形や select の、文字列だけのマッピングかどうか
+///
+/// キーが全て CLDR の複数形カテゴリなら、`other` が無くても複数形として扱う (other が無いエラーは plural_t! で返す)
+fn is_forms(value: &Value) -> bool {
+    if select_cases(value).is_some() {
+        return true;
+    }
+
+    let Value::Mapping(mapping) = value else {
+        return false;
+    };
+
+    is_plural_forms(mapping)
+        || (mapping.contains_key("other")
+            && mapping.values().all(|v| scalar_to_string(v).is_some()))
+}
+
+/// 1つの言語だけの yaml で、複数形や select のマッピングかどうか
+///
+/// 名前空間と区別できないので、`other` を含むだけのマッピングは select にしない
+fn is_locale_forms(value: &Value) -> bool {
+    match value {
+        Value::Mapping(mapping) => is_plural_forms(mapping),
+        value => select_cases(value).is_some(),
+    }
+}
+
+/// 名前空間をたどってキーを `.` でつなげる
+fn flatten(
+    prefix: &str,
+    mapping: &Mapping,
+    output: &mut HashMap<String, LocalizedText>,
+) -> Result<(), String> {
+    for (key, value) in mapping {
+        let Some(key) = scalar_to_string(key) else {
+            return Err(format!("Key in `{}` must be a string", prefix));
+        };
+
+        let full_key = match prefix.is_empty() {
+            true => key,
+            false => format!("{}.{}", prefix, key),
+        };
+
+        let Value::Mapping(child) = value else {
+            return Err(format!("Key: {} must be a mapping", full_key));
+        };
+
+        let is_translation = child
+            .values()
+            .any(|v| scalar_to_string(v).is_some() || is_forms(v));
+
+        if !is_translation && !child.is_empty() {
+            flatten(&full_key, child, output)?;
+            continue;
+        }
+
+        let localized_text = to_localized_text(&full_key, child)?;
+        if output.insert(full_key.clone(), localized_text).is_some() {
+            return Err(format!("Duplicate key: {}", full_key));
+        }
+    }
+
+    Ok(())
+}
+
+/// `other` もタグも無い select のケースが、名前空間として読まれていないか確認する
+///
+/// `replied: { en: { female: ..., male: ... } }` は `replied.en` というキーの
+/// `female` と `male` という言語になってしまうので、キーの最後が他で使われている言語で、
+/// その言語がその名前空間の外で使われていないならエラーにする
+fn check_select_without_other(yaml: &LangYaml) -> Result<(), String> {
+    // 言語ごとに、その言語を使っているキー
+    let mut lang_keys: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (key, localized_text) in yaml.iter() {
+        for lang in localized_text.langs() {
+            lang_keys.entry(lang).or_default().push(key);
+        }
+    }
+
+    let is_known_lang = |lang: &str| {
+        lang_keys.contains_key(lang)
+            || yaml.meta.contains_key(lang)
+            || yaml.fallback.contains_key(lang)
+            || yaml.default.as_deref() == Some(lang)
+    };
+
+    let mut keys: Vec<_> = yaml.keys().collect();
+    keys.sort();
+    for key in keys {
+        let Some((parent, last)) = key.rsplit_once('.') else {
+            continue;
+        };
+        if !is_known_lang(last) {
+            continue;
+        }
+
+        // 名前空間の外のキーで使われている言語か
+        let prefix = format!("{}.", parent);
+        let used_outside = |lang: &str| {
+            lang_keys[lang].iter().any(|k| !k.starts_with(&prefix))
+                || yaml.meta.contains_key(lang)
+                || yaml.fallback.contains_key(lang)
+                || yaml.default.as_deref() == Some(lang)
+        };
+
+        if !yaml[key].langs().any(|lang| used_outside(lang)) {
+            return Err(format!(
+                "Key: {} looks like select cases without `other` (add `other` or `!{}`)",
+                parent, SELECT_TAG
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// 1つの言語だけの yaml の名前空間をたどってキーを `.` でつなげる
+fn flatten_locale(
+    lang: &str,
+    prefix: &str,
+    mapping: &Mapping,
+    output: &mut HashMap<String, LocalizedText>,
+) -> Result<(), String> {
+    for (key, value) in mapping {
+        let Some(key) = scalar_to_string(key) else {
+            return Err(format!("Key in `{}` must be a string", prefix));
+        };
+
+        let full_key = match prefix.is_empty() {
+            true => key,
+            false => format!("{}.{}", prefix, key),
+        };
+
+        let value = match value {
+            Value::Mapping(child) if !is_locale_forms(value) => {
+                flatten_locale(lang, &full_key, child, output)?;
+                continue;
+            }
+
+            _ => {
+                let mut values = Mapping::new();
+                values.insert(Value::String(lang.to_string()), value.clone());
+                to_localized_text(&full_key, &values)?
+            }
+        };
+
+        if output.insert(full_key.clone(), value).is_some() {
+            return Err(format!("Duplicate key: {}", full_key));
+        }
+    }
+
+    Ok(())
+}
+
+/// 翻訳のマッピングを LocalizedText に変換する
+fn to_localized_text(key: &str, mapping: &Mapping) -> Result<LocalizedText, String> {
+    let mut values = HashMap::new();
+
+    for (lang, value) in mapping {
+        let Some(lang) = scalar_to_string(lang) else {
+            return Err(format!("Key: {}, language key must be a string", key));
+        };
+
+        let value = match value {
+            Value::Mapping(forms) => LangValue::Forms(to_forms(key, &lang, forms)?),
+
+            Value::Tagged(tagged) => match select_cases(value) {
+                Some(cases) => LangValue::Forms(to_forms(key, &lang, cases)?),
+                None => {
+                    return Err(format!(
+                        "Key: {}, language `{}` has unknown tag `{}`",
+                        key, lang, tagged.tag
+                    ))
+                }
+            },
+
+            value => match scalar_to_string(value) {
+                Some(text) => LangValue::Text(text),
+                None => {
+                    return Err(format!(
+                        "Key: {}, language `{}` must be a string",
+                        key, lang
+                    ))
+                }
+            },
+        };
+
+        values.insert(lang, value);
+    }
+
+    Ok(LocalizedText::from(values))
+}
+
+/// 複数形や select のマッピングを、形ごとの文字列に変換する
+fn to_forms(key: &str, lang: &str, forms: &Mapping) -> Result<BTreeMap<String, String>, String> {
+    let mut map = BTreeMap::new();
+
+    for (case, text) in forms {
+        match (scalar_to_string(case), scalar_to_string(text)) {
+            (Some(case), Some(text)) => map.insert(case, text),
+            _ => {
+                return Err(format!(
+                    "Key: {}, language `{}` must be a mapping of strings",
+                    key, lang
+                ))
+            }
+        };
+    }
+
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn sorted_keys(yaml: &LangYaml) -> Vec<&str> {
+        let mut keys: Vec<_> = yaml.keys().map(|k| k.as_str()).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn nested_keys() {
+        let yaml: LangYaml = serde_yaml::from_str(
+            r#"
+            top:
+              all: TOP
+            settings:
+              network:
+                proxy_title:
+                  en: Proxy
+                  ja: プロキシ
+                files:
+                  en:
+                    one: "{count} file"
+                    other: "{count} files"
+              empty: {}
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sorted_keys(&yaml),
+            [
+                "settings.empty",
+                "settings.network.files",
+                "settings.network.proxy_title",
+                "top"
+            ]
+        );
+        assert_eq!(yaml["settings.network.proxy_title"]["ja"], "プロキシ");
+        assert!(yaml["settings.network.files"].forms.contains_key("en"));
+    }
+
+    #[test]
+    fn forms_without_other() {
+        // 文字列の兄弟が無くても、複数形カテゴリだけのマッピングは翻訳として扱う
+        let yaml: LangYaml = serde_yaml::from_str(
+            r#"
+            items:
+              en:
+                one: "{count} item"
+              ja:
+                one: "{count}個"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(sorted_keys(&yaml), ["items"]);
+        assert!(yaml["items"].forms.contains_key("en"));
+        assert!(yaml["items"].forms.contains_key("ja"));
+    }
+
+    #[test]
+    fn select_without_other() {
+        let err = serde_yaml::from_str::<LangYaml>(
+            r#"
+            greeting:
+              en: Hello
+              ja: こんにちは
+            replied: { en: { female: …, male: … }, ja: { female: …, male: … } }
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains(
+            "Key: replied looks like select cases without `other` (add `other` or `!select`)"
+        ));
+    }
+
+    #[test]
+    fn duplicate_key() {
+        let err = serde_yaml::from_str::<LangYaml>(
+            r#"
+            a.b:
+              all: X
+            a:
+              b:
+                all: Y
+            "#,
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Duplicate key: a.b"));
+    }
+
+    #[test]
+    fn locale_keys() {
+        let yaml = LangYaml::from_locale_str(
+            "en",
+            r#"
+            hello: Hello
+            menu:
+              file: File
+            files:
+              one: "{count} file"
+              other: "{count} files"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(sorted_keys(&yaml), ["files", "hello", "menu.file"]);
+        assert_eq!(yaml["menu.file"]["en"], "File");
+        assert!(yaml["files"].forms.contains_key("en"));
+    }
+
+    #[test]
+    fn locale_other_namespace() {
+        // other というキーがあっても、複数形カテゴリ以外のキーがあれば名前空間
+        let yaml = LangYaml::from_locale_str(
+            "en",
+            r#"
+            errors:
+              other: Unknown error
+              network: Network error
+            replied: !select
+              female: She replied
+              other: They replied
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sorted_keys(&yaml),
+            ["errors.network", "errors.other", "replied"]
+        );
+        assert_eq!(yaml["errors.network"]["en"], "Network error");
+        assert_eq!(yaml["replied"].forms["en"]["female"], "She replied");
+
+        let err = LangYaml::from_locale_str(
+            "en",
+            "hello: !plural
+  one: x
+",
+        )
+        .unwrap_err();
+        assert!(err.contains("Key: hello, language `en` has unknown tag `!plural`"));
+    }
+
+    #[test]
+    fn fallback() {
+        let yaml: LangYaml = serde_yaml::from_str(
+            r#"
+            _fallback:
+              pt_br: pt
+              pt: [es, en]
+            hello:
+              en: Hello
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(sorted_keys(&yaml), ["hello"]);
+        assert_eq!(yaml.fallback["pt_br"], ["pt"]);
+        assert_eq!(yaml.fallback["pt"], ["es", "en"]);
+
+        let yaml = LangYaml::from_locale_str("pt_br", "_fallback: pt\nhello: Olá\n").unwrap();
+        assert_eq!(sorted_keys(&yaml), ["hello"]);
+        assert_eq!(yaml.fallback["pt_br"], ["pt"]);
+
+        let err = serde_yaml::from_str::<LangYaml>("_fallback:\n  pt: {a: b}\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Fallback of `pt` must be a language key or a list of language keys"));
+    }
+
+    #[test]
+    fn default_lang() {
+        let yaml: LangYaml = serde_yaml::from_str("_default: en\nhello:\n  en: Hello\n").unwrap();
+        assert_eq!(sorted_keys(&yaml), ["hello"]);
+        assert_eq!(yaml.default.as_deref(), Some("en"));
+
+        let err = serde_yaml::from_str::<LangYaml>("_default: [en]\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`_default` must be a language key"));
+    }
+
+    #[test]
+    fn meta() {
+        let yaml: LangYaml = serde_yaml::from_str(
+            r#"
+            _meta:
+              ja: { name: 日本語, tag: ja-JP }
+              ar: { dir: rtl }
+            hello:
+              ja: こんにちは
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(sorted_keys(&yaml), ["hello"]);
+        assert_eq!(yaml.meta["ja"].name.as_deref(), Some("日本語"));
+        assert_eq!(yaml.meta["ja"].tag.as_deref(), Some("ja-JP"));
+        assert_eq!(yaml.meta["ar"].dir.as_deref(), Some("rtl"));
+
+        let yaml = LangYaml::from_locale_str("ja", "_meta:\n  name: 日本語\n").unwrap();
+        assert_eq!(yaml.meta["ja"].name.as_deref(), Some("日本語"));
+
+        let err = serde_yaml::from_str::<LangYaml>("_meta:\n  ja: { nam: x }\n").unwrap_err();
+        assert!(err.to_string().contains("Failed to parse `_meta`: "));
+        assert!(err.to_string().contains("unknown field `nam`"));
+    }
+
+    #[test]
+    fn not_mapping() {
+        let err = serde_yaml::from_str::<LangYaml>("a:\n  b: [1, 2]\n").unwrap_err();
+
+        assert!(err.to_string().contains("Key: a.b must be a mapping"));
+    }
+}
@@ -25,6 +25,24 @@
 //!
 //! And the last character of the language key cannot be an underscore.
 //!
+//! Keys can be grouped into nested namespaces, and they are referred to by joining the keys with `.`.
+//!
+//! A mapping is a translation if at least one of its values is a string (or plural forms whose keys are CLDR plural categories, or select cases containing `other`),
+//!
+//! otherwise it is a namespace.
+//!
+//! ```yaml
+//! settings:
+//!   network:
+//!     proxy_title:
+//!       en: Proxy
+//!       ja: プロキシ
+//! ```
+//!
+//! ```rust,ignore
+//! println!("{}", lang_t!("settings.network.proxy_title", lang_en)); // Proxy
+//! ```
+//!
 //! ## Examples
 //!
 //! #### lang.yaml:
@@ -144,40 +162,181 @@
 //! println_t!("format_t_ex1", name); // Hi, Ferris!
 //! println_t!("format_t_ex2", lang_ja, name); // Ferris, おはよう!
 //! ```
+//!
+//! Named placeholders such as `{user}` are bound with `user = expr`, just like `format!`.
+//!
+//! The placeholders of every language are checked at compile time,
+//!
+//! so a language whose placeholders differ from the others, or missing / extra arguments, result in a compilation error.
+//!
+//! ```yaml
+//! format_t_ex3:
+//!   ja: "{user}さん、メールが{}件あります"
+//!   en: "Hi, {user}! You have {} mails"
+//! ```
+//!
+//! ```rust,ignore
+//! println_t!("format_t_ex3", lang_en, 3, user = name); // Hi, Ferris! You have 3 mails
+//! ```
+//!
+//! ## eprint_t!, eprintln_t!, write_t!, writeln_t!, panic_t!
+//!
+//! These take the same arguments as `format_t!` and expand to `eprint!`, `eprintln!`, `write!`, `writeln!` and `panic!`.
+//!
+//! `write_t!` and `writeln_t!` take the destination first (a `std::fmt::Write` or `std::io::Write`), so a `Display` impl can write the localized text without allocating a `String`.
+//!
+//! With a named catalog, pass the name before the destination (`write_t!(ui, f, "key", lang)`).
+//!
+//! ```rust,ignore
+//! impl std::fmt::Display for Greeting {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write_t!(f, "format_t_ex2", self.lang, self.name)
+//!     }
+//! }
+//!
+//! eprintln_t!("format_t_ex2", lang_en, name); // Ferris, Hello! (to stderr)
+//! ```
+//!
+//! ## format_args_t!
+//!
+//! `format_args_t!` takes the same arguments as `format_t!`, but returns a value that implements `Display` instead of a `String`.
+//!
+//! The arguments are borrowed and evaluated once, and the text is formatted without allocating when it is displayed,
+//!
+//! so it can be passed to `log`, `tracing` or `write!`. Like `format_args!`, use it within the same statement.
+//!
+//! ```rust,ignore
+//! log::info!("{}", format_args_t!("format_t_ex3", lang_en, 3, user = name));
+//! ```
+//!
+//! ## plural_t!
+//!
+//! A language value can also be a map of CLDR plural categories
+//!
+//! (`zero`, `one`, `two`, `few`, `many`, `other`). `other` is required.
+//!
+//! Pass the yaml key, the Lang Enum and the count, and then the thing you want to format.
+//!
+//! The form is selected at runtime with the CLDR plural rules of each language, and `{count}` is bound to the count.
+//!
+//! ```yaml
+//! plural_t_ex1:
+//!   en:
+//!     one: "{count} file"
+//!     other: "{count} files"
+//!   pl:
+//!     one: "{count} plik"
+//!     few: "{count} pliki"
+//!     many: "{count} plików"
+//!     other: "{count} pliku"
+//!   ja: "{count}個のファイル"
+//! ```
+//!
+//! ```rust,ignore
+//! println!("{}", plural_t!("plural_t_ex1", lang_en, 3)); // 3 files
+//! ```
+//!
+//! ## select_t!
+//!
+//! A language value can also be a map of any selector cases, such as grammatical gender.
+//!
+//! Every language that has cases must have `other`, even if all languages have the same cases,
+//!
+//! because the selector is a string chosen at runtime and `other` is the text for a selector that is not listed.
+//!
+//! Pass the yaml key, the Lang Enum and the selector (anything that implements `AsRef<str>`), and then the thing you want to format.
+//!
+//! The case is selected at runtime, and `other` is used when the language does not have the case.
+//!
+//! The `!select` tag (`en: !select { female: ..., other: ... }`) marks a mapping as select cases explicitly. Cases with neither `other` nor the tag are reported as an error instead of being read as a namespace.
+//!
+//! ```yaml
+//! select_t_ex1:
+//!   en:
+//!     female: "She replied to {}"
+//!     male: "He replied to {}"
+//!     other: "They replied to {}"
+//!   ja: "{}に返信しました"
+//! ```
+//!
+//! ```rust,ignore
+//! println!("{}", select_t!("select_t_ex1", lang_en, "female", name)); // She replied to Ferris
+//! ```
 
+mod catalog;
+mod format_args_t;
 mod format_t;
 mod i18n;
 mod lang_t;
 mod lang_yaml;
-mod print_t;
-mod println_t;
-
-use std::{
-    collections::HashSet,
-    sync::{LazyLock, Mutex, RwLock},
-    time::SystemTime,
-};
+mod plural_t;
+mod select_t;
 
-use format_t::_format_t;
+use format_args_t::_format_args_t;
+use format_t::{_format_macro_t, _format_t, format_macro::FormatMacro};
 use i18n::_i18n;
 use lang_t::_lang_t;
-use lang_yaml::LangYaml;
-use print_t::_print_t;
-use println_t::_println_t;
+use plural_t::_plural_t;
 use proc_macro::TokenStream;
-
-pub(crate) static YAML_PATH: LazyLock<Mutex<String>> = LazyLock::new(|| Mutex::new(String::new()));
-pub(crate) static YAML_MODIFIED_TIME: LazyLock<RwLock<SystemTime>> =
-    LazyLock::new(|| RwLock::new(SystemTime::now()));
-pub(crate) static YAML_DATA: LazyLock<Mutex<Option<LangYaml>>> = LazyLock::new(|| Mutex::new(None));
-pub(crate) static YAML_LANGS: LazyLock<Mutex<HashSet<String>>> =
-    LazyLock::new(|| Mutex::new(HashSet::new()));
+use select_t::_select_t;
 
 /// Enter the path of the yaml to be used and perform the initial settings.
 ///
 /// The enumeration is automatically generated according to the yaml, so branching by language uses that.
 ///
-/// Use this at the top of your main.rs or before you import any modules to set up your initial setup.
+/// Call it once at the root of your crate (main.rs or lib.rs), since the other macros look up the catalog through `crate::_langrustang_autogen`.
+///
+/// The other macros can be used anywhere in the crate, regardless of the order of the modules.
+///
+/// Relative paths are resolved from the directory containing the `Cargo.toml` of the crate (`CARGO_MANIFEST_DIR`),
+/// and absolute paths are used as they are.
+///
+/// Every yaml file that is read is included with `include_bytes!`, so editing it triggers a rebuild.
+/// (Adding a new file to a directory does not, so touch a source file in that case.)
+///
+/// Translations can also be split into multiple files by passing a list of paths.
+///
+/// - A `.yaml` file contains every language, in the usual format.
+/// - A directory contains one file per language, and the file name is the language key (e.g. `locales/en.yaml`).
+/// - A file name containing `*` or `?` is a glob, and every matching `.yaml` file is read in the usual format.
+///
+/// If the same key of the same language is defined in more than one file, it is a compilation error.
+///
+/// In a per-language file, a mapping is plural forms only if all of its keys are CLDR plural categories,
+/// and select cases are written with the `!select` tag (any other mapping is a namespace).
+///
+/// Pass `name = ...` first to generate the module with that name instead of `_langrustang_autogen`,
+/// and pass the same name as the first argument of the other macros to use it.
+///
+/// Pass `fallback = { "pt_br": "pt", "pt": "en" }` to choose the languages tried before `all`
+/// when a key does not have the language (a top-level `_fallback` mapping in the yaml does the same).
+///
+/// Pass `default = "en"` (or write `_default: en` in the yaml) to implement `Default` for `Lang`.
+/// Every key must then have a value for that language, and it is used before `all` when a key does not have the language.
+///
+/// `get(key, lang)` is also generated in the module to look up keys that are only known at runtime.
+///
+/// `Lang` has `ALL`, `COUNT`, `iter()` and `as_str()` (the yaml key), and implements `Ord`.
+///
+/// A top-level `_meta` mapping (`ja: { name: 日本語, tag: ja-JP, dir: ltr }`) generates
+/// `native_name()`, `bcp47()` and `direction()` for `Lang`.
+///
+/// `Lang::from_locale("en_US.UTF-8")` and `Lang::negotiate(&["fr", "ja-JP"])` pick the closest language for
+/// BCP 47 tags and POSIX locales, and `Lang::from_env()` reads `LANGUAGE`, `LC_ALL`, `LC_MESSAGES` and `LANG`.
+///
+/// With the `accept-language` feature (on by default), `Lang::from_accept_language(header)` is also generated.
+///
+/// Pass `serde = true` to implement `serde::Serialize` and `serde::Deserialize` for `Lang` with the yaml keys.
+///
+/// With the `clap` feature, `Lang` implements `clap::ValueEnum` with the native names as the help text.
+///
+/// `set_current_lang(lang)` and `current_lang()` are generated in the module, and `set_thread_lang(lang)` overrides the language in the current thread until the returned guard is dropped.
+///
+/// Write `@current` instead of the language in the other macros to use `current_lang()`.
+///
+/// Parsing an unknown language with `FromStr` returns `ParseLangError`.
+///
+/// Without the `std` feature (on by default), the module only uses `core` and `alloc`, and `from_env()` and `set_thread_lang()` are not generated.
 ///
 /// # Examples
 ///
@@ -195,6 +354,23 @@ pub(crate) static YAML_LANGS: LazyLock<Mutex<HashSet<String>>> =
 /// }
 ///
 /// ```
+///
+/// ```rust,ignore
+/// langrustang::i18n!(["common.yaml", "locales/", "extra/*.yaml"]);
+/// ```
+///
+/// ```yaml
+/// # locales/en.yaml
+/// hello: Hello
+/// menu:
+///   file: File
+/// ```
+///
+/// ```rust,ignore
+/// langrustang::i18n!(name = ui, "ui.yaml");  // Auto-generate `ui::Lang`
+///
+/// let s = langrustang::lang_t!(ui, "title", ui::Lang::En);
+/// ```
 #[proc_macro]
 pub fn i18n(tokens: TokenStream) -> TokenStream {
     _i18n(tokens.into()).into()
@@ -210,12 +386,52 @@ pub fn format_t(tokens: TokenStream) -> TokenStream {
     _format_t(tokens.into()).into()
 }
 
+#[proc_macro]
+pub fn format_args_t(tokens: TokenStream) -> TokenStream {
+    _format_args_t(tokens.into()).into()
+}
+
 #[proc_macro]
 pub fn print_t(tokens: TokenStream) -> TokenStream {
-    _print_t(tokens.into()).into()
+    _format_macro_t(FormatMacro::Print, tokens.into()).into()
 }
 
 #[proc_macro]
 pub fn println_t(tokens: TokenStream) -> TokenStream {
-    _println_t(tokens.into()).into()
+    _format_macro_t(FormatMacro::Println, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn eprint_t(tokens: TokenStream) -> TokenStream {
+    _format_macro_t(FormatMacro::Eprint, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn eprintln_t(tokens: TokenStream) -> TokenStream {
+    _format_macro_t(FormatMacro::Eprintln, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn write_t(tokens: TokenStream) -> TokenStream {
+    _format_macro_t(FormatMacro::Write, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn writeln_t(tokens: TokenStream) -> TokenStream {
+    _format_macro_t(FormatMacro::Writeln, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn panic_t(tokens: TokenStream) -> TokenStream {
+    _format_macro_t(FormatMacro::Panic, tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn plural_t(tokens: TokenStream) -> TokenStream {
+    _plural_t(tokens.into()).into()
+}
+
+#[proc_macro]
+pub fn select_t(tokens: TokenStream) -> TokenStream {
+    _select_t(tokens.into()).into()
 }
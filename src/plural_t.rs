@@ -0,0 +1,234 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{ParseStream, Parser},
+    spanned::Spanned,
+    Error, Ident, Result,
+};
+
+use crate::{
+    catalog::{self, Selected},
+    format_t::{format_macro, placeholder},
+    i18n::{check_yaml, plural_rules::CATEGORIES},
+};
+
+pub fn _plural_t(tokens: TokenStream) -> TokenStream {
+    plural_t_parse
+        .parse2(tokens)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+fn plural_t_parse(input: ParseStream) -> Result<TokenStream> {
+    let Some(catalog) = catalog::parse(input)? else {
+        // 使えない feature なら、i18n! を経由する前に返す
+        if let Err(err) = format_macro::check_feature("plural_t", "alloc", cfg!(feature = "alloc"))
+        {
+            return Err(Error::new(input.span(), err));
+        }
+
+        return catalog::redirect("plural_t", input);
+    };
+
+    let parsed = catalog.parse_args(input)?;
+
+    // 簡単にリターンできる用のクロージャ
+    let err_return = |s: String| Err(Error::new(parsed.span(), s));
+
+    if parsed.len() < 3 {
+        return Err(Error::new(
+            input.span(),
+            "Expected string literal, lang and count",
+        ));
+    }
+
+    // 指定されたキーの翻訳を取得
+    let (key, localized_text) = match catalog.localized_text(&parsed) {
+        Ok(found) => found,
+        Err(err) => return err_return(err),
+    };
+
+    // 複数形のカテゴリが正しいか、other があるか確認
+    let mut sorted_forms: Vec<_> = localized_text.forms.iter().collect();
+    sorted_forms.sort();
+
+    for (lang, forms) in sorted_forms {
+        if let Some(category) = forms.keys().find(|c| !CATEGORIES.contains(&c.as_str())) {
+            return err_return(format!(
+                "Key: {}, language `{}` has unknown plural category `{}`",
+                key, lang, category
+            ));
+        }
+
+        if !forms.contains_key("other") {
+            return err_return(format!(
+                "Key: {}, language `{}` is missing the `other` plural form",
+                key, lang
+            ));
+        }
+    }
+
+    // 言語ごとの値を選び、count 以外のプレースホルダが全ての形で一致しているか確認
+    let args: Vec<_> = parsed.iter().skip(3).collect();
+
+    let selected = match catalog.select_forms(&key, localized_text, &args, &["count"]) {
+        Ok(selected) => selected,
+        Err(err) => return err_return(err),
+    };
+
+    let module_path = catalog.module_path();
+    let format = quote! { #module_path::__langrustang_alloc::format };
+
+    // {count} が使われている時だけ count を渡す
+    let format_tokens = |text: &str| {
+        let uses_count = placeholder::parse(text)
+            .map(|p| p.named.contains("count"))
+            .unwrap_or(false);
+
+        match uses_count {
+            true => quote! { #format!(#text #(, #args)*, count = __langrustang_count) },
+            false => quote! { #format!(#text #(, #args)*) },
+        }
+    };
+
+    let lang_expr = &parsed[1];
+    let count_expr = &parsed[2];
+
+    let match_arms = selected.iter().map(|(lang, value)| {
+        let ident = Ident::new(&check_yaml::to_enumval_format(lang), Span::call_site());
+
+        match value {
+            Selected::Text(text) => {
+                let format = format_tokens(text);
+                quote! { #module_path::Lang::#ident => #format, }
+            }
+
+            Selected::Forms(forms) => {
+                let category_arms = CATEGORIES
+                    .iter()
+                    .filter(|category| **category != "other")
+                    .filter_map(|category| forms.get(*category).map(|text| (category, text)))
+                    .map(|(category, text)| {
+                        let category =
+                            Ident::new(&check_yaml::to_enumval_format(category), Span::call_site());
+                        let format = format_tokens(text);
+
+                        quote! {
+                            #module_path::PluralCategory::#category => #format,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let other = format_tokens(&forms["other"]);
+
+                match category_arms.is_empty() {
+                    true => quote! { #module_path::Lang::#ident => #other, },
+                    false => quote! {
+                        #module_path::Lang::#ident => match #module_path::Lang::#ident.plural_category(__langrustang_count) {
+                            #(#category_arms)*
+                            _ => #other,
+                        },
+                    },
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        {
+            let __langrustang_count = #count_expr;
+
+            match #lang_expr {
+                #(#match_arms)*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn dbg() {
+        let token =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural1", lang, n }).to_string();
+        dbg!(token);
+    }
+
+    #[test]
+    fn test_plural() {
+        let token1 =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural1", lang, n, dir })
+                .to_string();
+        let token2 = quote! {
+            {
+                let __langrustang_count = n;
+
+                match lang {
+                    crate::_langrustang_autogen::Lang::En => match crate::_langrustang_autogen::Lang::En.plural_category(__langrustang_count) {
+                        crate::_langrustang_autogen::PluralCategory::One => crate::_langrustang_autogen::__langrustang_alloc::format!("{count} file in {}", dir, count = __langrustang_count),
+                        _ => crate::_langrustang_autogen::__langrustang_alloc::format!("{count} files in {}", dir, count = __langrustang_count),
+                    },
+                    crate::_langrustang_autogen::Lang::Ja => crate::_langrustang_autogen::__langrustang_alloc::format!("{}に{count}個のファイル", dir, count = __langrustang_count),
+                    crate::_langrustang_autogen::Lang::Test1 => crate::_langrustang_autogen::__langrustang_alloc::format!("files in {}", dir),
+                    crate::_langrustang_autogen::Lang::Zh => crate::_langrustang_autogen::__langrustang_alloc::format!("files in {}", dir),
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2)
+    }
+
+    #[test]
+    fn missing_other() {
+        let token =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural2", lang, n }).to_string();
+        assert!(
+            dbg!(token).contains("Key: plural2, language `en` is missing the `other` plural form")
+        );
+
+        // all などの文字列の兄弟が無い場合
+        let token =
+            _plural_t(quote! { @catalog ("files/forms.yaml") "items", lang, n }).to_string();
+        assert!(
+            dbg!(token).contains("Key: items, language `en` is missing the `other` plural form")
+        );
+    }
+
+    #[test]
+    fn unknown_category() {
+        let token =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural3", lang, n }).to_string();
+        assert!(dbg!(token)
+            .contains("Key: plural3, language `en` has unknown plural category `single`"));
+    }
+
+    #[test]
+    fn placeholder_mismatch() {
+        let token =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural1", lang, n }).to_string();
+        assert!(
+            dbg!(token).contains("Key: plural1 expects 1 positional arguments, but 0 were given")
+        );
+    }
+
+    #[test]
+    fn expect_count() {
+        let token =
+            _plural_t(quote! { @catalog ("files/test_file.yaml") "plural1", lang }).to_string();
+        assert!(dbg!(token).contains("Expected string literal, lang and count"));
+    }
+
+    #[test]
+    fn lang_t_with_plural() {
+        let token =
+            crate::lang_t::_lang_t(quote! { @catalog ("files/test_file.yaml") "plural1", lang })
+                .to_string();
+        assert!(dbg!(token).contains("Key: plural1 has plural forms, use plural_t!"));
+    }
+}
@@ -0,0 +1,225 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{ParseStream, Parser},
+    spanned::Spanned,
+    Error, Ident, Result,
+};
+
+use crate::{
+    catalog::{self, Selected},
+    format_t::format_macro,
+    i18n::{check_yaml, plural_rules::CATEGORIES},
+    lang_yaml::LocalizedText,
+};
+
+pub fn _select_t(tokens: TokenStream) -> TokenStream {
+    select_t_parse
+        .parse2(tokens)
+        .unwrap_or_else(Error::into_compile_error)
+}
+
+/// 形ごとに分かれたキーを lang_t! などで使った時のエラー
+///
+/// CLDR の複数形カテゴリだけなら plural_t!、それ以外は select_t! を使うように伝える
+pub fn forms_error(key: &str, localized_text: &LocalizedText) -> String {
+    let is_plural = localized_text
+        .forms
+        .values()
+        .flat_map(|forms| forms.keys())
+        .all(|case| CATEGORIES.contains(&case.as_str()));
+
+    match is_plural {
+        true => format!("Key: {} has plural forms, use plural_t!", key),
+        false => format!("Key: {} has select cases, use select_t!", key),
+    }
+}
+
+fn select_t_parse(input: ParseStream) -> Result<TokenStream> {
+    let Some(catalog) = catalog::parse(input)? else {
+        // 使えない feature なら、i18n! を経由する前に返す
+        if let Err(err) = format_macro::check_feature("select_t", "alloc", cfg!(feature = "alloc"))
+        {
+            return Err(Error::new(input.span(), err));
+        }
+
+        return catalog::redirect("select_t", input);
+    };
+
+    let parsed = catalog.parse_args(input)?;
+
+    // 簡単にリターンできる用のクロージャ
+    let err_return = |s: String| Err(Error::new(parsed.span(), s));
+
+    if parsed.len() < 3 {
+        return Err(Error::new(
+            input.span(),
+            "Expected string literal, lang and selector",
+        ));
+    }
+
+    // 指定されたキーの翻訳を取得
+    let (key, localized_text) = match catalog.localized_text(&parsed) {
+        Ok(found) => found,
+        Err(err) => return err_return(err),
+    };
+
+    // セレクターは実行時の文字列なので、全ての言語が同じケースを持っていても、
+    // 書かれていないセレクターのために、ケースを持つ言語には other が必要
+    let mut sorted_cases: Vec<_> = localized_text.forms.iter().collect();
+    sorted_cases.sort();
+
+    for (lang, cases) in sorted_cases {
+        if !cases.contains_key("other") {
+            return err_return(format!(
+                "Key: {}, language `{}` is missing the `other` select case",
+                key, lang
+            ));
+        }
+    }
+
+    // 言語ごとの値を選び、プレースホルダが全てのケースで一致しているか確認
+    let args: Vec<_> = parsed.iter().skip(3).collect();
+
+    let selected = match catalog.select_forms(&key, localized_text, &args, &[]) {
+        Ok(selected) => selected,
+        Err(err) => return err_return(err),
+    };
+
+    let module_path = catalog.module_path();
+    let format = quote! { #module_path::__langrustang_alloc::format };
+
+    let format_tokens = |text: &str| quote! { #format!(#text #(, #args)*) };
+
+    let lang_expr = &parsed[1];
+    let selector_expr = &parsed[2];
+
+    let match_arms = selected.iter().map(|(lang, value)| {
+        let ident = Ident::new(&check_yaml::to_enumval_format(lang), Span::call_site());
+
+        match value {
+            Selected::Text(text) => {
+                let format = format_tokens(text);
+                quote! { #module_path::Lang::#ident => #format, }
+            }
+
+            Selected::Forms(cases) => {
+                let case_arms = cases
+                    .iter()
+                    .filter(|(case, _)| *case != "other")
+                    .map(|(case, text)| {
+                        let format = format_tokens(text);
+                        quote! { #case => #format, }
+                    })
+                    .collect::<Vec<_>>();
+
+                let other = format_tokens(&cases["other"]);
+
+                match case_arms.is_empty() {
+                    true => quote! { #module_path::Lang::#ident => #other, },
+                    false => quote! {
+                        #module_path::Lang::#ident => match __langrustang_selector {
+                            #(#case_arms)*
+                            _ => #other,
+                        },
+                    },
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        {
+            let __langrustang_selector = #selector_expr;
+            let __langrustang_selector: &str = ::core::convert::AsRef::<str>::as_ref(&__langrustang_selector);
+
+            match #lang_expr {
+                #(#match_arms)*
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn dbg() {
+        let token =
+            _select_t(quote! { @catalog ("files/test_file.yaml") "select1", lang, gender, name })
+                .to_string();
+        dbg!(token);
+    }
+
+    #[test]
+    fn test_select() {
+        let token1 =
+            _select_t(quote! { @catalog ("files/test_file.yaml") "select1", lang, gender, name })
+                .to_string();
+        let token2 = quote! {
+            {
+                let __langrustang_selector = gender;
+                let __langrustang_selector: &str = ::core::convert::AsRef::<str>::as_ref(&__langrustang_selector);
+
+                match lang {
+                    crate::_langrustang_autogen::Lang::En => match __langrustang_selector {
+                        "female" => crate::_langrustang_autogen::__langrustang_alloc::format!("She replied to {}", name),
+                        "male" => crate::_langrustang_autogen::__langrustang_alloc::format!("He replied to {}", name),
+                        _ => crate::_langrustang_autogen::__langrustang_alloc::format!("They replied to {}", name),
+                    },
+                    crate::_langrustang_autogen::Lang::Ja => crate::_langrustang_autogen::__langrustang_alloc::format!("{}に返信しました", name),
+                    crate::_langrustang_autogen::Lang::Test1 => crate::_langrustang_autogen::__langrustang_alloc::format!("replied to {}", name),
+                    crate::_langrustang_autogen::Lang::Zh => crate::_langrustang_autogen::__langrustang_alloc::format!("replied to {}", name),
+                }
+            }
+        }
+        .to_string();
+
+        assert_eq!(token1, token2)
+    }
+
+    #[test]
+    fn missing_other() {
+        let token = _select_t(quote! { @catalog ("files/test_file.yaml") "select2", lang, gender })
+            .to_string();
+        assert!(
+            dbg!(token).contains("Key: select2, language `en` is missing the `other` select case")
+        );
+
+        // 全ての言語が同じケースを持っていても other が必要
+        let token =
+            _select_t(quote! { @catalog ("files/forms.yaml") "replied", lang, gender }).to_string();
+        assert!(
+            dbg!(token).contains("Key: replied, language `en` is missing the `other` select case")
+        );
+    }
+
+    #[test]
+    fn placeholder_mismatch() {
+        let token = _select_t(quote! { @catalog ("files/test_file.yaml") "select1", lang, gender })
+            .to_string();
+        assert!(
+            dbg!(token).contains("Key: select1 expects 1 positional arguments, but 0 were given")
+        );
+    }
+
+    #[test]
+    fn expect_selector() {
+        let token =
+            _select_t(quote! { @catalog ("files/test_file.yaml") "select1", lang }).to_string();
+        assert!(dbg!(token).contains("Expected string literal, lang and selector"));
+    }
+
+    #[test]
+    fn lang_t_with_select() {
+        let token =
+            crate::lang_t::_lang_t(quote! { @catalog ("files/test_file.yaml") "select1", lang })
+                .to_string();
+        assert!(dbg!(token).contains("Key: select1 has select cases, use select_t!"));
+    }
+}
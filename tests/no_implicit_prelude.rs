@@ -0,0 +1,120 @@
+//! 生成されたコードが prelude や、利用者の同じ名前のマクロ、モジュールに依存しないかのテスト
+#![no_implicit_prelude]
+
+::langrustang::i18n!("files/default.yaml", serde = true);
+
+use _langrustang_autogen::Lang;
+
+// 利用者が定義した同じ名前のマクロやモジュール
+#[allow(unused_macros)]
+macro_rules! format {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!("user-defined format! is used")
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! write {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!("user-defined write! is used")
+    };
+}
+
+#[allow(unused_macros)]
+macro_rules! println {
+    ($($tokens:tt)*) => {
+        ::core::compile_error!("user-defined println! is used")
+    };
+}
+
+#[allow(dead_code)]
+mod std {}
+
+#[allow(dead_code)]
+mod core {}
+
+#[allow(dead_code)]
+enum Option {
+    Some,
+    None,
+}
+
+#[test]
+fn lang() {
+    ::std::assert_eq!(::langrustang::lang_t!("hello", Lang::En), "Hello");
+    ::std::assert_eq!(
+        _langrustang_autogen::get("hello", Lang::Ja),
+        ::core::option::Option::Some("こんにちは")
+    );
+
+    ::std::assert_eq!(::std::format!("{}", Lang::Ja), "Ja");
+    ::std::assert_eq!("en".parse::<Lang>(), ::core::result::Result::Ok(Lang::En));
+    ::std::assert_eq!(
+        ::core::iter::ExactSizeIterator::len(&Lang::iter()),
+        Lang::COUNT
+    );
+    ::std::assert_eq!(<Lang as ::core::default::Default>::default(), Lang::Ja);
+
+    ::std::assert_eq!(
+        Lang::from_locale("en_US.UTF-8"),
+        ::core::option::Option::Some(Lang::En)
+    );
+    ::std::assert_eq!(
+        Lang::negotiate(&["fr", "ja-JP"]),
+        ::core::option::Option::Some(Lang::Ja)
+    );
+    #[cfg(feature = "accept-language")]
+    ::std::assert_eq!(
+        Lang::from_accept_language("fr, en;q=0.5"),
+        ::core::option::Option::Some(Lang::En)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn format() {
+    let name = "Ferris";
+
+    ::std::assert_eq!(::langrustang::format_t!("hello", Lang::En), "Hello");
+    ::std::assert_eq!(
+        ::langrustang::format_t!("replied_plain", Lang::En, name, count = 2),
+        "Ferris replied 2 times"
+    );
+    ::std::assert_eq!(::langrustang::plural_t!("files", Lang::En, 1), "1 file");
+    ::std::assert_eq!(
+        ::langrustang::select_t!("replied", Lang::En, "male", name),
+        "Ferris replied, he said"
+    );
+    ::std::assert_eq!(
+        ::std::format!(
+            "{}",
+            ::langrustang::format_args_t!("replied_plain", Lang::Ja, name, count = 3)
+        ),
+        "Ferrisが3回返信しました"
+    );
+
+    // write! と同じく、書き込み先のトレイトは利用者が use する
+    use ::std::fmt::Write as _;
+    let mut out = ::std::string::String::new();
+    ::langrustang::write_t!(out, "hello", Lang::En).unwrap();
+    ::langrustang::writeln_t!(&mut out, "hello", @current).unwrap();
+    ::std::assert!(out.starts_with("Hello"));
+
+    ::langrustang::print_t!("hello", Lang::En);
+    ::langrustang::println_t!("hello", Lang::En);
+    ::langrustang::eprint_t!("hello", Lang::En);
+    ::langrustang::eprintln_t!("hello", Lang::En);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn current_lang() {
+    let _guard = _langrustang_autogen::set_thread_lang(Lang::En);
+    ::std::assert_eq!(::langrustang::lang_t!("hello", @current), "Hello");
+}
+
+#[test]
+#[should_panic(expected = "Hello")]
+fn panic() {
+    ::langrustang::panic_t!("hello", Lang::En);
+}
@@ -0,0 +1,88 @@
+//! 生成されたコードと lang_t! が std を使わずに動くかのテスト
+//!
+//! std の feature が有効だと std を使うコードが生成されるので、無効な時だけ実行する
+#![cfg(not(feature = "std"))]
+#![no_std]
+
+use core::fmt::{self, Write as _};
+
+langrustang::i18n!("files/default.yaml");
+
+use _langrustang_autogen::Lang;
+
+/// 固定長のバッファに書き込む
+struct Buffer {
+    bytes: [u8; 64],
+    len: usize,
+}
+
+impl Buffer {
+    fn new() -> Self {
+        Buffer {
+            bytes: [0; 64],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+impl fmt::Write for Buffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[test]
+fn lang() {
+    assert_eq!(langrustang::lang_t!("hello", Lang::En), "Hello");
+    assert_eq!(langrustang::lang_t!("bye", Lang::En), "さようなら");
+    assert_eq!(
+        _langrustang_autogen::get("hello", Lang::Ja),
+        Some("こんにちは")
+    );
+    assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+
+    _langrustang_autogen::set_current_lang(Lang::En);
+    assert_eq!(langrustang::lang_t!("hello", @current), "Hello");
+}
+
+#[test]
+fn parse_error() {
+    assert_eq!("ja".parse::<Lang>(), Ok(Lang::Ja));
+
+    let err = "fr".parse::<Lang>().unwrap_err();
+    let mut buf = Buffer::new();
+    write!(buf, "{}", err).unwrap();
+    assert_eq!(buf.as_str(), "unknown language key");
+}
+
+#[test]
+fn write() {
+    let mut buf = Buffer::new();
+    langrustang::write_t!(buf, "replied_plain", Lang::En, "Ferris", count = 2).unwrap();
+    assert_eq!(buf.as_str(), "Ferris replied 2 times");
+
+    let mut buf = Buffer::new();
+    write!(buf, "{}", langrustang::format_args_t!("hello", Lang::Ja)).unwrap();
+    assert_eq!(buf.as_str(), "こんにちは");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn format() {
+    assert_eq!(langrustang::format_t!("hello", Lang::En), "Hello");
+    assert_eq!(langrustang::plural_t!("files", Lang::En, 2), "2 files");
+    assert_eq!(
+        langrustang::select_t!("replied", Lang::En, "female", "Ferris"),
+        "Ferris replied, she said"
+    );
+}
@@ -0,0 +1,388 @@
+langrustang::i18n!(name = meta, "files/meta.yaml", serde = true);
+langrustang::i18n!(name = fallback, "files/fallback.yaml");
+langrustang::i18n!(name = default, "files/default.yaml");
+langrustang::i18n!(name = plural, "files/plural.yaml");
+
+#[test]
+fn from_locale() {
+    use meta::Lang;
+
+    assert_eq!(Lang::from_locale("ja"), Some(Lang::Ja));
+    assert_eq!(Lang::from_locale("ja-JP"), Some(Lang::Ja));
+    assert_eq!(Lang::from_locale("en_US.UTF-8"), Some(Lang::En));
+    assert_eq!(Lang::from_locale("en_GB@euro"), Some(Lang::En));
+    assert_eq!(Lang::from_locale("zh-Hant-TW"), Some(Lang::ZhTw));
+    assert_eq!(Lang::from_locale("zh_HK"), Some(Lang::ZhTw));
+    // 地域の無い zh は簡体字なので、繁体字の zh_tw にはならない
+    assert_eq!(Lang::from_locale("zh"), None);
+    // 簡体字は繁体字の言語にしない
+    assert_eq!(Lang::from_locale("zh-CN"), None);
+    assert_eq!(Lang::from_locale("zh-Hans"), None);
+    assert_eq!(Lang::negotiate(&["zh-CN", "ja"]), Some(Lang::Ja));
+    assert_eq!(Lang::from_locale("AR"), Some(Lang::Ar));
+    assert_eq!(Lang::from_locale("fr-FR"), None);
+    assert_eq!(Lang::from_locale("C"), None);
+    assert_eq!(Lang::from_locale(""), None);
+}
+
+#[test]
+fn negotiate() {
+    use fallback::Lang;
+
+    assert_eq!(Lang::negotiate(&["fr", "pt-BR", "en"]), Some(Lang::PtBr));
+    assert_eq!(Lang::negotiate(&["pt-PT", "en"]), Some(Lang::Pt));
+    assert_eq!(Lang::negotiate(&["pt"]), Some(Lang::Pt));
+    assert_eq!(Lang::negotiate(&["de", "fr"]), None);
+    assert_eq!(Lang::negotiate(&[]), None);
+}
+
+#[test]
+fn plural_category() {
+    use plural::{Lang, PluralCategory::*};
+
+    const COUNTS: [u64; 12] = [0, 1, 2, 3, 5, 11, 12, 21, 22, 101, 111, 1_000_000];
+
+    let expected = [
+        (
+            Lang::Ar,
+            [
+                Zero, One, Two, Few, Few, Many, Many, Many, Many, Other, Many, Other,
+            ],
+        ),
+        (
+            Lang::Cy,
+            [
+                Zero, One, Two, Few, Other, Other, Other, Other, Other, Other, Other, Other,
+            ],
+        ),
+        (
+            Lang::Fr,
+            [
+                One, One, Other, Other, Other, Other, Other, Other, Other, Other, Other, Many,
+            ],
+        ),
+        (
+            Lang::Lv,
+            [
+                Zero, One, Other, Other, Other, Zero, Zero, One, Other, One, Zero, Zero,
+            ],
+        ),
+        (
+            Lang::Pl,
+            [
+                Many, One, Few, Few, Many, Many, Many, Many, Few, Many, Many, Many,
+            ],
+        ),
+        (
+            Lang::Ro,
+            [
+                Few, One, Few, Few, Few, Few, Few, Other, Other, Few, Few, Other,
+            ],
+        ),
+        (
+            Lang::Ru,
+            [
+                Many, One, Few, Few, Many, Many, Many, One, Few, One, Many, Many,
+            ],
+        ),
+    ];
+
+    for (lang, categories) in expected {
+        assert_eq!(
+            COUNTS.map(|n| lang.plural_category(n)),
+            categories,
+            "{}",
+            lang
+        );
+    }
+
+    // 負の数は絶対値で選ぶ
+    assert_eq!(Lang::Ru.plural_category(-21), One);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn from_env() {
+    use meta::Lang;
+
+    // 環境変数を変えるのはこのテストだけにする
+    let set = |lc_all: &str, lc_messages: &str, lang: &str, language: &str| {
+        std::env::set_var("LC_ALL", lc_all);
+        std::env::set_var("LC_MESSAGES", lc_messages);
+        std::env::set_var("LANG", lang);
+        std::env::set_var("LANGUAGE", language);
+    };
+
+    set("", "", "ja_JP.UTF-8", "");
+    assert_eq!(Lang::from_env(), Some(Lang::Ja));
+
+    set("", "en_US.UTF-8", "ja_JP.UTF-8", "");
+    assert_eq!(Lang::from_env(), Some(Lang::En));
+
+    set("ar_EG.UTF-8", "en_US.UTF-8", "ja_JP.UTF-8", "");
+    assert_eq!(Lang::from_env(), Some(Lang::Ar));
+
+    // LANGUAGE の優先順位のリスト
+    set("", "", "en_US.UTF-8", "fr:zh_TW:ja");
+    assert_eq!(Lang::from_env(), Some(Lang::ZhTw));
+
+    set("", "", "ja_JP.UTF-8", "fr:de");
+    assert_eq!(Lang::from_env(), Some(Lang::Ja));
+
+    // ロケールが C なら LANGUAGE は使わない
+    set("C", "", "", "ja");
+    assert_eq!(Lang::from_env(), None);
+
+    set("", "", "", "ja");
+    assert_eq!(Lang::from_env(), None);
+}
+
+#[cfg(feature = "accept-language")]
+#[test]
+fn from_accept_language() {
+    use meta::Lang;
+
+    assert_eq!(Lang::from_accept_language("ja"), Some(Lang::Ja));
+    assert_eq!(
+        Lang::from_accept_language("fr-CH, fr;q=0.9, en;q=0.8, ja;q=0.7"),
+        Some(Lang::En)
+    );
+    assert_eq!(
+        Lang::from_accept_language("en;q=0.5, zh-TW;q=0.9"),
+        Some(Lang::ZhTw)
+    );
+    assert_eq!(
+        Lang::from_accept_language("en-GB;q=0.8, ja-JP;q=0.8"),
+        Some(Lang::En)
+    );
+    assert_eq!(Lang::from_accept_language("fr, *;q=0.1"), Some(Lang::Ar));
+    // * は他の範囲で指定されていない言語になる
+    assert_eq!(
+        Lang::from_accept_language("fr, *;q=0.1, ar;q=0"),
+        Some(Lang::En)
+    );
+    assert_eq!(
+        Lang::from_accept_language("ar;q=0.1, *;q=0.5"),
+        Some(Lang::En)
+    );
+    assert_eq!(Lang::from_accept_language("ja;q=0, en;q=abc"), None);
+    assert_eq!(Lang::from_accept_language(""), None);
+
+    // * はデフォルトの言語を使う
+    assert_eq!(
+        default::Lang::from_accept_language("fr, *;q=0.5"),
+        Some(default::Lang::Ja)
+    );
+}
+
+#[test]
+fn serde() {
+    use meta::Lang;
+
+    assert_eq!(serde_yaml::to_string(&Lang::ZhTw).unwrap(), "zh_tw\n");
+    assert_eq!(
+        serde_yaml::to_string(&[Lang::Ja, Lang::En]).unwrap(),
+        "- ja\n- en\n"
+    );
+
+    for value in ["zh_tw", "ZhTw", "zhtw", "ZHTW"] {
+        assert_eq!(serde_yaml::from_str::<Lang>(value).unwrap(), Lang::ZhTw);
+    }
+
+    let err = serde_yaml::from_str::<Lang>("fr").unwrap_err();
+    assert!(err.to_string().contains("unknown variant `fr`"));
+}
+
+#[cfg(feature = "clap")]
+#[test]
+fn clap_value_enum() {
+    use clap::ValueEnum;
+    use meta::Lang;
+
+    let values: Vec<_> = Lang::value_variants()
+        .iter()
+        .map(|lang| lang.to_possible_value().unwrap())
+        .collect();
+    assert_eq!(values[3].get_name(), "zh_tw");
+    assert_eq!(values[2].get_help().unwrap().to_string(), "日本語");
+
+    assert_eq!(Lang::from_str("zh_tw", false), Ok(Lang::ZhTw));
+    assert_eq!(Lang::from_str("JA", true), Ok(Lang::Ja));
+    assert!(Lang::from_str("fr", true).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn current_lang() {
+    use default::Lang;
+
+    // 最初はデフォルトの言語
+    assert_eq!(default::current_lang(), Lang::Ja);
+    assert_eq!(
+        langrustang::lang_t!(default, "hello", @current),
+        "こんにちは"
+    );
+
+    default::set_current_lang(Lang::En);
+    assert_eq!(default::current_lang(), Lang::En);
+    assert_eq!(langrustang::lang_t!(default, "hello", @current), "Hello");
+
+    // スレッドの言語はガードを drop すると戻る
+    {
+        let _guard = default::set_thread_lang(Lang::Ja);
+        assert_eq!(
+            langrustang::lang_t!(default, "hello", @current),
+            "こんにちは"
+        );
+
+        {
+            let _guard = default::set_thread_lang(Lang::En);
+            assert_eq!(default::current_lang(), Lang::En);
+        }
+        assert_eq!(default::current_lang(), Lang::Ja);
+
+        // 他のスレッドには影響しない
+        let other = std::thread::spawn(default::current_lang).join().unwrap();
+        assert_eq!(other, Lang::En);
+    }
+    assert_eq!(default::current_lang(), Lang::En);
+
+    assert_eq!(langrustang::format_t!(default, "hello", @current), "Hello");
+}
+
+#[test]
+fn parse_error() {
+    use default::Lang;
+
+    assert_eq!("EN".parse::<Lang>(), Ok(Lang::En));
+
+    let err = "fr".parse::<Lang>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown language key");
+    assert_eq!(err, "".parse::<Lang>().unwrap_err());
+
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(err.to_string(), "unknown language key");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn select() {
+    use default::Lang;
+
+    assert_eq!(
+        langrustang::select_t!(default, "replied", Lang::En, "female", "Ferris"),
+        "Ferris replied, she said"
+    );
+    assert_eq!(
+        langrustang::select_t!(default, "replied", Lang::En, String::from("male"), "Ferris"),
+        "Ferris replied, he said"
+    );
+    assert_eq!(
+        langrustang::select_t!(default, "replied", Lang::En, "unknown", "Ferris"),
+        "Ferris replied, they said"
+    );
+    assert_eq!(
+        langrustang::select_t!(default, "replied", Lang::Ja, "female", "Ferris"),
+        "Ferrisが返信しました"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn write() {
+    use std::fmt::{self, Write as _};
+
+    use default::Lang;
+
+    struct Greeting(Lang);
+
+    impl fmt::Display for Greeting {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            langrustang::write_t!(default, f, "hello", self.0)
+        }
+    }
+
+    assert_eq!(Greeting(Lang::En).to_string(), "Hello");
+    assert_eq!(Greeting(Lang::Ja).to_string(), "こんにちは");
+
+    let mut out = String::new();
+    langrustang::writeln_t!(default, &mut out, "hello", Lang::En).unwrap();
+    langrustang::write_t!(default, out, "bye", Lang::En).unwrap();
+    assert_eq!(out, "Hello\nさようなら");
+
+    // io::Write にも書き込める
+    use std::io::Write as _;
+    let mut buf = Vec::new();
+    langrustang::writeln_t!(default, buf, "hello", Lang::Ja).unwrap();
+    assert_eq!(buf, "こんにちは\n".as_bytes());
+
+    langrustang::eprintln_t!(default, "hello", Lang::En);
+}
+
+#[test]
+#[should_panic(expected = "さようなら")]
+fn panic() {
+    langrustang::panic_t!(default, "bye", default::Lang::En);
+}
+
+#[test]
+fn format_args() {
+    use default::Lang;
+
+    let name = String::from("Ferris");
+    let message = langrustang::format_args_t!(default, "replied_plain", Lang::En, name, count = 2);
+    assert_eq!(message.to_string(), "Ferris replied 2 times");
+    assert_eq!(format!("{:?}", message), "Ferris replied 2 times");
+    assert_eq!(
+        format!(
+            "[{}]",
+            langrustang::format_args_t!(
+                default,
+                "replied_plain",
+                Lang::Ja,
+                "Ferris",
+                count = 1 + 1
+            )
+        ),
+        "[Ferrisが2回返信しました]"
+    );
+
+    // 引数は一度だけ評価される
+    let mut calls = 0;
+    let mut next = || {
+        calls += 1;
+        calls
+    };
+    let text = format!(
+        "{0} {0}",
+        langrustang::format_args_t!(default, "replied_plain", @current, "Ferris", count = next())
+    );
+    assert!(text.contains("1 times") || text.contains("1回"));
+    assert_eq!(calls, 1);
+
+    assert_eq!(
+        langrustang::format_args_t!(default, "hello", Lang::En).to_string(),
+        "Hello"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn print_format_binding() {
+    // 引数の中の format! や format という名前はそのまま使われる
+    let format = "Ferris";
+    langrustang::print_t!(
+        default,
+        "replied_plain",
+        default::Lang::En,
+        format,
+        count = format.len()
+    );
+    langrustang::println_t!(
+        default,
+        "replied_plain",
+        default::Lang::Ja,
+        format!("{format}!"),
+        count = 1
+    );
+}